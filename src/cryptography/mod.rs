@@ -1,2 +1,3 @@
+pub mod hashing;
 pub mod private_key;
 pub mod signature;
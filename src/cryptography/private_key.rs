@@ -1,63 +1,377 @@
-use rug::{rand::RandState, Integer};
+use std::fmt;
 
-use crate::elliptic_curve::point::Point;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 
-use super::signature::Signature;
+use crate::elliptic_curve::{curve::Curve, point::Point, scalar::Scalar};
+use crate::encoding::base58::{self, Base58Error};
+use crate::encoding::fixed_width::to_be_bytes_32;
+use crate::integer::{BigInteger, Integer};
+use crate::network::Network;
 
-static N: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+use super::{hashing::hash256, signature::Signature};
+
+type HmacSha256 = Hmac<Sha256>;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct PrivateKey {
-    secret: Integer,
+    secret: Scalar,
     point: Point,
 }
 
+#[derive(PartialEq, Debug)]
+pub enum PrivateKeyError {
+    InvalidWif(String),
+    InvalidChecksum(String),
+    SecretOutOfRange(String),
+}
+
 impl PrivateKey {
-    pub fn new(secret: Integer) -> PrivateKey {
-        PrivateKey {
-            secret: secret.clone(),
-            point: secret * Point::g_point(),
+    /// Builds the key with the given secret, which must not be zero.
+    pub fn new(secret: Scalar) -> Result<PrivateKey, PrivateKeyError> {
+        if secret.is_zero() {
+            return Err(secret_out_of_range());
+        }
+
+        Ok(PrivateKey {
+            point: Curve::secp256k1().mul_generator(secret.value()),
+            secret,
+        })
+    }
+
+    pub fn secret(&self) -> &Scalar {
+        &self.secret
+    }
+
+    pub fn point(&self) -> &Point {
+        &self.point
+    }
+
+    /// Serializes the secret in Wallet Import Format: the network version
+    /// byte, the 32 byte secret and, for keys whose public point is used
+    /// compressed, a `0x01` suffix, all in Base58Check.
+    pub fn wif(&self, compressed: bool, network: Network) -> String {
+        let mut payload = vec![network.wif_prefix()];
+        payload.extend(to_be_bytes_32(self.secret.value()));
+        if compressed {
+            payload.push(0x01);
         }
+        base58::encode_check(&payload)
     }
 
-    pub fn secret(self) -> Integer {
-        self.secret
+    /// Parses a WIF private key, returning it with its compression flag and
+    /// network.
+    pub fn from_wif(wif: &str) -> Result<(PrivateKey, bool, Network), PrivateKeyError> {
+        let payload = base58::decode_check(wif).map_err(|e| match e {
+            Base58Error::InvalidChecksum(message) => PrivateKeyError::InvalidChecksum(message),
+            e => PrivateKeyError::InvalidWif(e.to_string()),
+        })?;
+
+        let compressed = match payload.len() {
+            33 => false,
+            34 if payload[33] == 0x01 => true,
+            _ => {
+                return Err(PrivateKeyError::InvalidWif(format!(
+                    "{} is not a WIF private key",
+                    wif
+                )))
+            }
+        };
+        let network = Network::from_wif_prefix(payload[0]).ok_or_else(|| {
+            PrivateKeyError::InvalidWif(format!("unknown WIF version byte {:#04x}", payload[0]))
+        })?;
+
+        let secret = Scalar::new(Integer::from_be_bytes(&payload[1..33]))
+            .map_err(|_| secret_out_of_range())?;
+        Ok((PrivateKey::new(secret)?, compressed, network))
     }
 
-    pub fn point(self) -> Point {
-        self.point
+    pub fn sign(&self, z: &Integer) -> Signature {
+        let k = self.deterministic_k(z, None);
+        self.sign_with_k(z, &k)
     }
 
-    pub fn sign(self, z: Integer) -> Signature {
-        let n = Integer::from_str_radix(N, 16).unwrap();
-        let mut rand = RandState::new();
+    /// Signs the hash256 digest of `message`.
+    pub fn sign_message(&self, message: &[u8]) -> Signature {
+        self.sign(&Integer::from_be_bytes(&hash256(message)))
+    }
+
+    /// Signs `z` mixing `extra_entropy` into the nonce derivation, as described
+    /// in RFC 6979 section 3.6. The result is still deterministic for a given
+    /// `(secret, z, extra_entropy)` triple.
+    pub fn sign_with_extra_entropy(&self, z: &Integer, extra_entropy: &[u8; 32]) -> Signature {
+        let k = self.deterministic_k(z, Some(extra_entropy));
+        self.sign_with_k(z, &k)
+    }
+
+    /// Derives the nonce `k` for `z` following RFC 6979 with HMAC-SHA256.
+    pub fn deterministic_k(&self, z: &Integer, extra_entropy: Option<&[u8; 32]>) -> Scalar {
+        let mut k = [0u8; 32];
+        let mut v = [1u8; 32];
+
+        let z_bytes = to_be_bytes_32(Scalar::reduce(z).value());
+        let secret_bytes = to_be_bytes_32(self.secret.value());
+        let extra: &[u8] = match extra_entropy {
+            Some(extra) => extra,
+            None => &[],
+        };
 
-        let k = n.clone().random_below(&mut rand);
+        k = hmac_sha256(&k, &[&v, &[0x00], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
+        k = hmac_sha256(&k, &[&v, &[0x01], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
 
-        let r = (k.clone() * Point::g_point()).x().unwrap().num();
-        let k_inv = k.pow_mod(&(n.clone() - 2i32), &n).unwrap();
-        let mut s = (z + r.clone() * self.secret) * k_inv % &n;
-        if s > n.clone() / 2i32 {
-            s = n - s;
+        loop {
+            v = hmac_sha256(&k, &[&v]);
+            if let Ok(candidate) = Scalar::new(Integer::from_be_bytes(&v)) {
+                if !candidate.is_zero() {
+                    return candidate;
+                }
+            }
+            k = hmac_sha256(&k, &[&v, &[0x00]]);
+            v = hmac_sha256(&k, &[&v]);
+        }
+    }
+
+    fn sign_with_k(&self, z: &Integer, k: &Scalar) -> Signature {
+        let r = self.point.curve().mul_generator(k.value());
+        let r = Scalar::reduce(&r.x().unwrap().num());
+        let k_inv = k.invert().unwrap();
+        let mut s = (&r * &self.secret + Scalar::reduce(z)) * k_inv;
+        if s.is_high() {
+            s = -s;
         }
         Signature::new(r, s)
     }
 }
 
+impl fmt::Display for PrivateKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrivateKeyError::InvalidWif(message)
+            | PrivateKeyError::InvalidChecksum(message)
+            | PrivateKeyError::SecretOutOfRange(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PrivateKeyError {}
+
+fn secret_out_of_range() -> PrivateKeyError {
+    PrivateKeyError::SecretOutOfRange(String::from("secret not in range 1 to n - 1"))
+}
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    mac.finalize().into_bytes().into()
+}
+
 #[cfg(test)]
 mod tests {
-    use rug::ops::Pow;
+    use rand::{rngs::StdRng, SeedableRng};
 
     use super::*;
 
+    #[test]
+    fn test_new() {
+        let pk = PrivateKey::new(Scalar::from(1u32)).unwrap();
+        assert_eq!(pk.point(), &Point::g_point());
+
+        assert_eq!(
+            PrivateKey::new(Scalar::from(0u32)),
+            Err(PrivateKeyError::SecretOutOfRange(String::from(
+                "secret not in range 1 to n - 1"
+            )))
+        );
+    }
+
     #[test]
     fn test_sign() {
-        let n = Integer::from_str_radix(N, 16).unwrap();
-        let mut rand = RandState::new();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let pk =
+            PrivateKey::new(Scalar::new(Scalar::order().sample_below(&mut rng)).unwrap()).unwrap();
+        let z = Integer::from(2i32).pow(256).sample_below(&mut rng);
+        let sig = pk.sign(&z);
+        assert!(pk.point().verify(&z, &sig));
+    }
+
+    #[test]
+    fn test_sign_is_deterministic() {
+        let pk = PrivateKey::new(Scalar::from(12345u32)).unwrap();
+        let z = hex("a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e");
+
+        assert_eq!(pk.sign(&z), pk.sign(&z));
+    }
+
+    // RFC 6979 test vectors for secp256k1 with SHA-256. `z` is the SHA-256
+    // digest of the message shown in the comment.
+    #[test]
+    fn test_rfc6979_vectors() {
+        let vectors = [
+            (
+                // "Satoshi Nakamoto"
+                Scalar::from(1u32),
+                "a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e",
+                "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                // "All those moments will be lost in time, like tears in rain. Time to die..."
+                Scalar::from(1u32),
+                "7d1833f54854ac51659521afcd0ec6dca2ce2351429614bfa28a756b1b3c637f",
+                "38aa22d72376b4dbc472e06c3ba403ee0a394da63fc58d88686c611aba98d6b3",
+                "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b",
+                "547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21",
+            ),
+            (
+                // "Satoshi Nakamoto"
+                -Scalar::from(1u32),
+                "a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e",
+                "33a19b60e25fb6f4435af53a3d42d493644827367e6453928554f43e49aa6f90",
+                "fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d0",
+                "6b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5",
+            ),
+            (
+                // "Alan Turing"
+                scalar("f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181"),
+                "4ba38d48a60f1b29e9eb726eaff08b2e83d8d81e031666fee50e85900d7dc1ef",
+                "525a82b70e67874398067543fd84c83d30c175fdc45fdeee082fe13b1d7cfdf1",
+                "7063ae83e7f62bbb171798131b4a0564b956930092b33b07b395615d9ec7e15c",
+                "58dfcc1e00a35e1572f366ffe34ba0fc47db1e7189759b9fb233c5b05ab388ea",
+            ),
+            (
+                // "There is a computer disease that anybody who works with computers knows
+                // about. It's a very serious disease and it interferes completely with the
+                // work. The trouble with computers is that you 'play' with them!"
+                scalar("e91671c46231f833a6406ccbea0e3e392c76c167bac1cb013f6f1013980455c2"),
+                "1609a53bb33ef00e0cc1e784b436d7924956d87ec2b399574378312f07cba3e8",
+                "1f4b84c23a86a221d233f2521be018d9318639d5b8bbd6374a8a59232d16ad3d",
+                "b552edd27580141f3b2a5463048cb7cd3e047b97c9f98076c32dbdf85a68718b",
+                "279fa72dd19bfae05577e06c7c0c1900c371fcd5893f7e1d56a37d30174671f6",
+            ),
+        ];
+
+        for (secret, z, k, r, s) in vectors {
+            let pk = PrivateKey::new(secret).unwrap();
+            let z = hex(z);
+
+            assert_eq!(pk.deterministic_k(&z, None), scalar(k));
+
+            let sig = pk.sign(&z);
+            assert_eq!(sig, Signature::new(scalar(r), scalar(s)));
+            assert!(pk.point().verify(&z, &sig));
+        }
+    }
+
+    #[test]
+    fn test_sign_with_extra_entropy() {
+        let pk = PrivateKey::new(Scalar::from(1u32)).unwrap();
+        let z = hex("a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e");
+        let extra_entropy = [1u8; 32];
+
+        assert_eq!(
+            pk.deterministic_k(&z, Some(&extra_entropy)),
+            scalar("ff49282725ee554d481ee92230ebf201d5137cdc427fcda67210387e20a1b90b")
+        );
+
+        let sig = pk.sign_with_extra_entropy(&z, &extra_entropy);
+        assert_eq!(
+            sig,
+            Signature::new(
+                scalar("bb6cf569458d507451271380d2863dad30355387836d5c3287a4efbd5ed1ad8e"),
+                scalar("4bb4b7899e803f760fe89027e55f5d93768983d6e28af4b5722f6226b345380e")
+            )
+        );
+        assert_ne!(sig, pk.sign(&z));
+        assert!(pk.point().verify(&z, &sig));
+    }
+
+    #[test]
+    fn test_sign_message() {
+        let pk = PrivateKey::new(Scalar::from(12345u32)).unwrap();
+        let message = b"Programming Bitcoin!";
+        let z = hex("969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48");
+
+        let sig = pk.sign_message(message);
+        assert_eq!(sig, pk.sign(&z));
+        assert!(pk.point().verify_message(message, &sig));
+        assert!(!pk.point().verify_message(b"Programming Bitcoin?", &sig));
+    }
+
+    #[test]
+    fn test_wif() {
+        let cases = [
+            (
+                Integer::from(5003i32),
+                true,
+                Network::Testnet,
+                "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK",
+            ),
+            (
+                Integer::from(2021i32).pow(5),
+                false,
+                Network::Testnet,
+                "91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic",
+            ),
+            (
+                Integer::from(0x54321deadbeefi64),
+                true,
+                Network::Mainnet,
+                "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a",
+            ),
+        ];
+
+        for (secret, compressed, network, wif) in cases {
+            let pk = PrivateKey::new(Scalar::new(secret).unwrap()).unwrap();
+            assert_eq!(pk.wif(compressed, network), wif);
+            assert_eq!(
+                PrivateKey::from_wif(wif).unwrap(),
+                (pk, compressed, network)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_wif_rejects_invalid_keys() {
+        let cases = [
+            // checksum altered
+            "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53b",
+            // secret equal to n
+            "L5oLkpV3aqBjhki6LmvChTCV6odsp4SXM6FfU2Gppt5kFqRzExJJ",
+            // secret zero
+            "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73Nd2Mcv1",
+            // compression flag 0x02
+            "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sfZr2ym",
+            // version byte 0x81
+            "L5oLkpV3aqBjhki6LmvChTCq73v9gyymzzMpBbhDLjDpLCfkwaDM",
+            // a P2PKH address
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            // not base58
+            "0KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a",
+        ];
+        let errors: Vec<_> = cases
+            .iter()
+            .map(|wif| PrivateKey::from_wif(wif).unwrap_err())
+            .collect();
+
+        assert!(matches!(errors[0], PrivateKeyError::InvalidChecksum(_)));
+        assert!(matches!(errors[1], PrivateKeyError::SecretOutOfRange(_)));
+        assert!(matches!(errors[2], PrivateKeyError::SecretOutOfRange(_)));
+        for error in &errors[3..] {
+            assert!(matches!(error, PrivateKeyError::InvalidWif(_)));
+        }
+    }
+
+    fn hex(value: &str) -> Integer {
+        Integer::from_radix_str(value, 16).unwrap()
+    }
 
-        let pk = PrivateKey::new(n.random_below(&mut rand));
-        let z = (Integer::from(2i32).pow(256)).random_below(&mut RandState::new());
-        let sig = pk.clone().sign(z.clone());
-        assert!(pk.point().verify(z, sig));
+    fn scalar(value: &str) -> Scalar {
+        Scalar::new(hex(value)).unwrap()
     }
 }
@@ -1,25 +1,205 @@
 use std::fmt;
 
-use rug::Integer;
+use crate::elliptic_curve::scalar::Scalar;
+use crate::integer::{BigInteger, Integer};
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Signature {
-    r: Integer,
-    s: Integer,
+    r: Scalar,
+    s: Scalar,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SignatureError {
+    InvalidDerLength(String),
+    InvalidDerMarker(String),
+    NegativeInteger(String),
+    ExcessPadding(String),
+    IntegerOutOfRange(String),
 }
 
 impl Signature {
-    pub fn new(r: Integer, s: Integer) -> Signature {
+    pub fn new(r: Scalar, s: Scalar) -> Signature {
         Signature { r, s }
     }
 
-    pub fn s(self) -> Integer {
-        self.s
+    pub fn s(&self) -> &Scalar {
+        &self.s
+    }
+
+    pub fn r(&self) -> &Scalar {
+        &self.r
+    }
+
+    /// Serializes the signature as `0x30 len 0x02 len(r) r 0x02 len(s) s`,
+    /// using the minimal big-endian encoding of both integers.
+    pub fn der(&self) -> Vec<u8> {
+        let r = der_integer(self.r.value());
+        let s = der_integer(self.s.value());
+
+        let mut der = vec![0x30, (r.len() + s.len()) as u8];
+        der.extend(r);
+        der.extend(s);
+        der
+    }
+
+    /// Parses a DER signature (without sighash byte) following the strict
+    /// encoding rules of BIP66. The values are secp256k1 scalars.
+    pub fn parse_der(der: &[u8]) -> Result<Signature, SignatureError> {
+        if der.len() < 8 || der.len() > 72 {
+            return Err(SignatureError::InvalidDerLength(format!(
+                "DER signature must be 8 to 72 bytes long, got {}",
+                der.len()
+            )));
+        }
+        if der[0] != 0x30 {
+            return Err(SignatureError::InvalidDerMarker(format!(
+                "expected compound marker 0x30, got {:#04x}",
+                der[0]
+            )));
+        }
+        if der[1] as usize != der.len() - 2 {
+            return Err(SignatureError::InvalidDerLength(format!(
+                "declared length {} does not match {} remaining bytes",
+                der[1],
+                der.len() - 2
+            )));
+        }
+
+        let r_len = der[3] as usize;
+        if 5 + r_len >= der.len() {
+            return Err(SignatureError::InvalidDerLength(format!(
+                "r length {} overflows the signature",
+                r_len
+            )));
+        }
+        let s_len = der[5 + r_len] as usize;
+        if r_len + s_len + 6 != der.len() {
+            return Err(SignatureError::InvalidDerLength(format!(
+                "r length {} and s length {} do not add up to the signature length",
+                r_len, s_len
+            )));
+        }
+
+        let r = parse_der_integer(&der[2..4 + r_len], "r")?;
+        let s = parse_der_integer(&der[4 + r_len..], "s")?;
+
+        Ok(Signature { r, s })
+    }
+
+    /// Parses a signature the way Bitcoin Core does without strict encoding,
+    /// following `ecdsa_signature_parse_der_lax`: lengths may use the long
+    /// form, the sequence length is ignored, integers may be padded with
+    /// zeros and anything after `s` is ignored. The values are secp256k1
+    /// scalars.
+    pub fn parse_der_lax(der: &[u8]) -> Result<Signature, SignatureError> {
+        if der.first() != Some(&0x30) {
+            return Err(SignatureError::InvalidDerMarker(String::from(
+                "expected compound marker 0x30",
+            )));
+        }
+        let mut rest = &der[1..];
+        let len_byte = take(&mut rest, 1, "sequence length")?[0];
+        if len_byte & 0x80 != 0 {
+            take(&mut rest, (len_byte & 0x7f) as usize, "sequence length")?;
+        }
+
+        let r = parse_lax_integer(&mut rest, "r")?;
+        let s = parse_lax_integer(&mut rest, "s")?;
+
+        Ok(Signature { r, s })
+    }
+}
+
+fn der_integer(value: &Integer) -> Vec<u8> {
+    let mut bytes = value.to_be_bytes();
+    if bytes.first().is_none_or(|b| b & 0x80 != 0) {
+        bytes.insert(0, 0x00);
     }
 
-    pub fn r(self) -> Integer {
-        self.r
+    let mut der = vec![0x02, bytes.len() as u8];
+    der.extend(bytes);
+    der
+}
+
+// `element` holds the marker, the length and the big-endian value, with the
+// length already checked against the enclosing signature.
+fn parse_der_integer(element: &[u8], name: &str) -> Result<Scalar, SignatureError> {
+    if element[0] != 0x02 {
+        return Err(SignatureError::InvalidDerMarker(format!(
+            "expected integer marker 0x02 for {}, got {:#04x}",
+            name, element[0]
+        )));
     }
+
+    let value = &element[2..];
+    if value.is_empty() {
+        return Err(SignatureError::InvalidDerLength(format!(
+            "{} has zero length",
+            name
+        )));
+    }
+    if value[0] & 0x80 != 0 {
+        return Err(SignatureError::NegativeInteger(format!(
+            "{} is negative",
+            name
+        )));
+    }
+    if value.len() > 1 && value[0] == 0x00 && value[1] & 0x80 == 0 {
+        return Err(SignatureError::ExcessPadding(format!(
+            "{} has an unnecessary leading zero byte",
+            name
+        )));
+    }
+
+    Scalar::new(Integer::from_be_bytes(value)).map_err(|_| {
+        SignatureError::IntegerOutOfRange(format!("{} is not below the group order", name))
+    })
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize, name: &str) -> Result<&'a [u8], SignatureError> {
+    if len > rest.len() {
+        return Err(SignatureError::InvalidDerLength(format!(
+            "{} overflows the signature",
+            name
+        )));
+    }
+    let (taken, remaining) = rest.split_at(len);
+    *rest = remaining;
+    Ok(taken)
+}
+
+fn parse_lax_integer(rest: &mut &[u8], name: &str) -> Result<Scalar, SignatureError> {
+    if rest.first() != Some(&0x02) {
+        return Err(SignatureError::InvalidDerMarker(format!(
+            "expected integer marker 0x02 for {}",
+            name
+        )));
+    }
+    *rest = &rest[1..];
+
+    let len_byte = take(rest, 1, name)?[0];
+    let len = if len_byte & 0x80 != 0 {
+        let mut len_bytes = take(rest, (len_byte & 0x7f) as usize, name)?;
+        while let [0, tail @ ..] = len_bytes {
+            len_bytes = tail;
+        }
+        if len_bytes.len() >= std::mem::size_of::<usize>() {
+            return Err(SignatureError::InvalidDerLength(format!(
+                "{} length does not fit in memory",
+                name
+            )));
+        }
+        len_bytes
+            .iter()
+            .fold(0, |len, &byte| (len << 8) | byte as usize)
+    } else {
+        len_byte as usize
+    };
+
+    Scalar::new(Integer::from_be_bytes(take(rest, len, name)?)).map_err(|_| {
+        SignatureError::IntegerOutOfRange(format!("{} is not below the group order", name))
+    })
 }
 
 impl fmt::Display for Signature {
@@ -27,3 +207,191 @@ impl fmt::Display for Signature {
         write!(f, "Signature({},{})", self.r, self.s)
     }
 }
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignatureError::InvalidDerLength(message)
+            | SignatureError::InvalidDerMarker(message)
+            | SignatureError::NegativeInteger(message)
+            | SignatureError::ExcessPadding(message)
+            | SignatureError::IntegerOutOfRange(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::elliptic_curve::point::Point;
+
+    use super::*;
+
+    #[test]
+    fn test_der() {
+        let sig = Signature::new(
+            scalar("37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6"),
+            scalar("8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"),
+        );
+        let der = "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c60221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec";
+
+        assert_eq!(hex::encode(sig.der()), der);
+        assert_eq!(
+            Signature::parse_der(&hex::decode(der).unwrap()).unwrap(),
+            sig
+        );
+    }
+
+    #[test]
+    fn test_der_round_trip() {
+        let values = [
+            Scalar::from(1u32),
+            Scalar::from(0x7fu32),
+            Scalar::from(0x80u32),
+            Scalar::from(0xff00u32),
+            scalar("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140"),
+        ];
+
+        for r in values.iter() {
+            for s in values.iter() {
+                let sig = Signature::new(r.clone(), s.clone());
+                assert_eq!(Signature::parse_der(&sig.der()).unwrap(), sig);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_der_rejects_non_strict_encodings() {
+        let valid = hex::decode("3006020101020101").unwrap();
+        assert!(Signature::parse_der(&valid).is_ok());
+
+        let cases = [
+            // too short
+            "30050201010201",
+            // wrong compound marker
+            "3106020101020101",
+            // declared length too long
+            "3007020101020101",
+            // r length overflows
+            "3006020901020101",
+            // s length does not match
+            "3006020101020201",
+            // wrong integer marker for r
+            "3006030101020101",
+            // wrong integer marker for s
+            "3006020101030101",
+            // zero length r
+            "3006020002020101",
+            // negative r
+            "3006020181020101",
+            // negative s
+            "3006020101020181",
+            // excess padding in r
+            "300702020001020101",
+            // excess padding in s
+            "300702010102020001",
+        ];
+
+        for case in cases {
+            assert!(
+                Signature::parse_der(&hex::decode(case).unwrap()).is_err(),
+                "{}",
+                case
+            );
+        }
+
+        assert_eq!(
+            Signature::parse_der(&hex::decode("3006020181020101").unwrap()),
+            Err(SignatureError::NegativeInteger(String::from(
+                "r is negative"
+            )))
+        );
+
+        // s equal to the group order n
+        let der =
+            "3026020101022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+        assert_eq!(
+            Signature::parse_der(&hex::decode(der).unwrap()),
+            Err(SignatureError::IntegerOutOfRange(String::from(
+                "s is not below the group order"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parse_der_lax() {
+        let expected = Signature::new(Scalar::from(1u32), Scalar::from(0x80u32));
+
+        let cases = [
+            // strict DER
+            "300702010102020080",
+            // ignored sequence length, padded integers and trailing data
+            "30100203000001020200800000",
+            // long form lengths
+            "3081ff02810101028200020080",
+            // zero length bytes in front of the long form length
+            "3007020101028300000200800000",
+        ];
+        for case in cases {
+            assert_eq!(
+                Signature::parse_der_lax(&hex::decode(case).unwrap()),
+                Ok(expected.clone()),
+                "{}",
+                case
+            );
+        }
+
+        let cases = [
+            // empty
+            "",
+            // wrong compound marker
+            "3107020101020200800000",
+            // missing sequence length
+            "30",
+            // long form sequence length overflows
+            "3085020101",
+            // wrong integer marker
+            "3007030101020200800000",
+            // s length overflows
+            "300702010102030080",
+            // missing s
+            "3003020101",
+        ];
+        for case in cases {
+            assert!(
+                Signature::parse_der_lax(&hex::decode(case).unwrap()).is_err(),
+                "{}",
+                case
+            );
+        }
+
+        // A strict signature parses the same both ways.
+        let der = hex::decode("3045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed").unwrap();
+        assert_eq!(Signature::parse_der_lax(&der), Signature::parse_der(&der));
+    }
+
+    #[test]
+    fn test_verify_transaction_signature() {
+        // First input of the transaction parsed in chapter 5 of the book,
+        // with the signature hash computed in chapter 7.
+        let der = hex::decode("3045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed").unwrap();
+        let sec = hex::decode("0349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a")
+            .unwrap();
+        let z = hex("27e0c5994dec7824e56dec6b2fcb342eb7cdb0d0957c2fce9882f715e85d81a6");
+
+        let sig = Signature::parse_der(&der).unwrap();
+        let point = Point::parse_sec(&sec).unwrap();
+
+        assert_eq!(sig.der(), der);
+        assert!(point.verify(&z, &sig));
+    }
+
+    fn hex(value: &str) -> Integer {
+        Integer::from_radix_str(value, 16).unwrap()
+    }
+
+    fn scalar(value: &str) -> Scalar {
+        Scalar::new(hex(value)).unwrap()
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    finite_field::field_element::FieldElement,
+    integer::{BigInteger, Integer},
+};
+
+use super::{generator_table::GeneratorTable, jacobian::JacobianPoint, point::Point};
+
+/// Short Weierstrass curve `y^2 = x^3 + ax + b` over the prime field of order
+/// `p`, together with a generator point of order `n` and the cofactor `h`.
+#[derive(Debug)]
+pub struct Curve {
+    p: Integer,
+    a: FieldElement,
+    b: FieldElement,
+    gx: FieldElement,
+    gy: FieldElement,
+    n: Arc<Integer>,
+    h: Integer,
+    generator_table: OnceLock<Option<GeneratorTable>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum CurveError {
+    InvalidParameter(String),
+    SingularCurve(String),
+    GeneratorNotInCurve(String),
+}
+
+impl Curve {
+    pub fn new(
+        p: Integer,
+        a: Integer,
+        b: Integer,
+        gx: Integer,
+        gy: Integer,
+        n: Integer,
+        h: Integer,
+    ) -> Result<Arc<Curve>, CurveError> {
+        let discriminant = a.clone().pow(3) * 4i32 + b.clone().pow(2) * 27i32;
+        if discriminant.is_multiple_of(&p) {
+            return Err(CurveError::SingularCurve(format!(
+                "y^2 = x^3 + {}x + {} has zero discriminant modulo {}",
+                a, b, p
+            )));
+        }
+
+        let element = |value: Integer| {
+            FieldElement::new(value, p.clone())
+                .map_err(|e| CurveError::InvalidParameter(e.to_string()))
+        };
+        let curve = Curve {
+            a: element(a)?,
+            b: element(b)?,
+            gx: element(gx)?,
+            gy: element(gy)?,
+            p,
+            n: Arc::new(n),
+            h,
+            generator_table: OnceLock::new(),
+        };
+
+        if !curve.contains(&curve.gx, &curve.gy) {
+            return Err(CurveError::GeneratorNotInCurve(format!(
+                "({}, {}) is not on the curve",
+                curve.gx, curve.gy
+            )));
+        }
+
+        Ok(Arc::new(curve))
+    }
+
+    /// The curve used by Bitcoin: `y^2 = x^3 + 7` over `2^256 - 2^32 - 977`.
+    /// Built once and shared, so its generator table is only computed once.
+    pub fn secp256k1() -> Arc<Curve> {
+        static SECP256K1: OnceLock<Arc<Curve>> = OnceLock::new();
+        SECP256K1
+            .get_or_init(|| {
+                Curve::new(
+                    Integer::from(2i32).pow(256)
+                        - Integer::from(2i32).pow(32)
+                        - Integer::from(977i32),
+                    Integer::from(0i32),
+                    Integer::from(7i32),
+                    hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                    hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+                    hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+                    Integer::from(1i32),
+                )
+                .unwrap()
+            })
+            .clone()
+    }
+
+    /// NIST P-256.
+    pub fn secp256r1() -> Arc<Curve> {
+        Curve::new(
+            hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"),
+            hex("ffffffff00000001000000000000000000000000fffffffffffffffffffffffc"),
+            hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+            hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+            Integer::from(1i32),
+        )
+        .unwrap()
+    }
+
+    /// The toy curve `y^2 = x^3 + 7` over F223 used in chapter 3 of the book,
+    /// with the generator (47, 71) of order 21.
+    pub fn f223() -> Arc<Curve> {
+        Curve::new(
+            Integer::from(223i32),
+            Integer::from(0i32),
+            Integer::from(7i32),
+            Integer::from(47i32),
+            Integer::from(71i32),
+            Integer::from(21i32),
+            Integer::from(12i32),
+        )
+        .unwrap()
+    }
+
+    pub fn contains(&self, x: &FieldElement, y: &FieldElement) -> bool {
+        y.pow(&Integer::from(2i32)) == x.pow(&Integer::from(3i32)) + &self.a * x + &self.b
+    }
+
+    pub fn generator(self: &Arc<Self>) -> Point {
+        Point::new(self.gx.clone(), self.gy.clone(), self).unwrap()
+    }
+
+    /// Multiplies the generator by `scalar` using a table of precomputed
+    /// multiples, built the first time it is needed. The lookups and the
+    /// sequence of point additions do not depend on the scalar, but the
+    /// additions themselves are not constant-time, see `GeneratorTable::mul`.
+    pub fn mul_generator(self: &Arc<Self>, scalar: &Integer) -> Point {
+        self.mul_generator_jacobian(scalar).to_affine()
+    }
+
+    pub(crate) fn mul_generator_jacobian(self: &Arc<Self>, scalar: &Integer) -> JacobianPoint {
+        match self
+            .generator_table
+            .get_or_init(|| GeneratorTable::new(self))
+        {
+            Some(table) => table.mul(self, scalar),
+            None => JacobianPoint::from_affine(&self.generator()).mul_ladder(scalar),
+        }
+    }
+
+    pub fn prime(&self) -> &Integer {
+        &self.p
+    }
+
+    pub fn a(&self) -> &FieldElement {
+        &self.a
+    }
+
+    pub fn b(&self) -> &FieldElement {
+        &self.b
+    }
+
+    pub fn order(&self) -> &Integer {
+        &self.n
+    }
+
+    // Shared with the scalars modulo the order, which keep a reference to it.
+    pub(crate) fn shared_order(&self) -> &Arc<Integer> {
+        &self.n
+    }
+
+    pub fn cofactor(&self) -> &Integer {
+        &self.h
+    }
+}
+
+// The generator table is derived from the other fields, so it is left out.
+impl PartialEq for Curve {
+    fn eq(&self, other: &Curve) -> bool {
+        self.p == other.p
+            && self.a == other.a
+            && self.b == other.b
+            && self.gx == other.gx
+            && self.gy == other.gy
+            && self.n == other.n
+            && self.h == other.h
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Curve(y^2 = x^3 + {}x + {} mod {})",
+            self.a.num(),
+            self.b.num(),
+            self.p
+        )
+    }
+}
+
+impl fmt::Display for CurveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CurveError::InvalidParameter(message)
+            | CurveError::SingularCurve(message)
+            | CurveError::GeneratorNotInCurve(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CurveError {}
+
+fn hex(value: &str) -> Integer {
+    Integer::from_radix_str(value, 16).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_singular_curves() {
+        let curve = Curve::new(
+            Integer::from(223i32),
+            Integer::from(0i32),
+            Integer::from(0i32),
+            Integer::from(0i32),
+            Integer::from(0i32),
+            Integer::from(1i32),
+            Integer::from(1i32),
+        );
+        assert!(matches!(curve, Err(CurveError::SingularCurve(_))));
+
+        // 4 * (-3)^3 + 27 * 2^2 = 0
+        let curve = Curve::new(
+            Integer::from(223i32),
+            Integer::from(220i32),
+            Integer::from(2i32),
+            Integer::from(1i32),
+            Integer::from(0i32),
+            Integer::from(1i32),
+            Integer::from(1i32),
+        );
+        assert!(matches!(curve, Err(CurveError::SingularCurve(_))));
+    }
+
+    #[test]
+    fn test_rejects_invalid_generator() {
+        let curve = Curve::new(
+            Integer::from(223i32),
+            Integer::from(0i32),
+            Integer::from(7i32),
+            Integer::from(200i32),
+            Integer::from(119i32),
+            Integer::from(21i32),
+            Integer::from(12i32),
+        );
+        assert!(matches!(curve, Err(CurveError::GeneratorNotInCurve(_))));
+
+        let curve = Curve::new(
+            Integer::from(223i32),
+            Integer::from(0i32),
+            Integer::from(7i32),
+            Integer::from(223i32),
+            Integer::from(71i32),
+            Integer::from(21i32),
+            Integer::from(12i32),
+        );
+        assert!(matches!(curve, Err(CurveError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_rejects_composite_order() {
+        let curve = Curve::new(
+            Integer::from(221i32),
+            Integer::from(0i32),
+            Integer::from(7i32),
+            Integer::from(1i32),
+            Integer::from(1i32),
+            Integer::from(1i32),
+            Integer::from(1i32),
+        );
+        assert_eq!(
+            curve.unwrap_err(),
+            CurveError::InvalidParameter(String::from("Order 221 is not prime"))
+        );
+    }
+
+    #[test]
+    fn test_generator_order() {
+        for curve in [Curve::secp256k1(), Curve::secp256r1(), Curve::f223()] {
+            let g = curve.generator();
+            assert_eq!(curve.order().clone() * g, Point::infinity(&curve));
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_is_shared() {
+        assert!(Arc::ptr_eq(&Curve::secp256k1(), &Curve::secp256k1()));
+        assert!(Arc::ptr_eq(Point::g_point().curve(), &Curve::secp256k1()));
+    }
+
+    #[test]
+    fn test_mul_generator() {
+        for curve in [Curve::secp256k1(), Curve::secp256r1(), Curve::f223()] {
+            for k in [0i32, 1, 2, 20, 21, 1000, 123456789] {
+                let k = Integer::from(k);
+                assert_eq!(curve.mul_generator(&k), k.clone() * curve.generator());
+            }
+        }
+    }
+
+    #[test]
+    fn test_f223_group_order() {
+        let curve = Curve::f223();
+        let mut points = 1;
+        for x in 0..223 {
+            for y in 0..223 {
+                let x = FieldElement::new(Integer::from(x), Integer::from(223i32)).unwrap();
+                let y = FieldElement::new(Integer::from(y), Integer::from(223i32)).unwrap();
+                if curve.contains(&x, &y) {
+                    points += 1;
+                }
+            }
+        }
+
+        assert_eq!(
+            Integer::from(points),
+            curve.order().clone() * curve.cofactor()
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    finite_field::field_element::FieldElement,
+    integer::{BigInteger, Integer},
+};
+
+use super::{curve::Curve, jacobian::JacobianPoint};
+
+const WINDOW_BITS: u32 = 4;
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+
+/// Fixed-base table for multiplying the generator `G` of a curve.
+///
+/// Window `i` holds `(j + 1) * 16^i * G` for `j` in `0..16`, in affine
+/// coordinates. Storing `j + 1` instead of `j` keeps the point at infinity out
+/// of the table: the scalar is shifted down by `sum(16^i)` beforehand, and
+/// every window contributes exactly one entry to the result. The table only
+/// keeps coordinates, not the curve, so a curve can own its own table.
+pub(crate) struct GeneratorTable {
+    windows: Vec<Vec<(FieldElement, FieldElement)>>,
+    offset: Integer,
+}
+
+impl GeneratorTable {
+    /// Builds the table, or returns `None` if some entry is the point at
+    /// infinity, which can only happen when the order of `G` is not prime.
+    pub(crate) fn new(curve: &Arc<Curve>) -> Option<GeneratorTable> {
+        let count = curve.order().bits().div_ceil(WINDOW_BITS);
+        let mut entries = Vec::with_capacity(count as usize * WINDOW_SIZE);
+        let mut offset = Integer::from(0i32);
+
+        let mut base = JacobianPoint::from_affine(&curve.generator());
+        for i in 0..count {
+            let mut entry = base.clone();
+            for j in 0..WINDOW_SIZE {
+                if j > 0 {
+                    entry = entry.add(&base);
+                }
+                entries.push(entry.clone());
+            }
+            offset += Integer::from(1i32) << (WINDOW_BITS * i);
+            base = entry;
+        }
+
+        let affine = normalize(curve, entries)?;
+        let windows = affine
+            .chunks(WINDOW_SIZE)
+            .map(|window| window.to_vec())
+            .collect();
+        Some(GeneratorTable { windows, offset })
+    }
+
+    /// Computes `scalar * G`. Every entry of every window is read, and the
+    /// wanted one is picked with `FieldElement::select`, so the table access
+    /// pattern and the sequence of point additions do not depend on the scalar.
+    /// As with the ladder, the additions themselves are not constant-time.
+    pub(crate) fn mul(&self, curve: &Arc<Curve>, scalar: &Integer) -> JacobianPoint {
+        let shifted = (scalar.clone() - &self.offset).rem_euclid(curve.order());
+
+        let mut result = JacobianPoint::infinity(curve);
+        for (i, window) in self.windows.iter().enumerate() {
+            let digit = window_digit(&shifted, i as u32);
+            let (mut x, mut y) = window[0].clone();
+            for (j, (entry_x, entry_y)) in window.iter().enumerate().skip(1) {
+                x = FieldElement::select(&x, entry_x, j == digit);
+                y = FieldElement::select(&y, entry_y, j == digit);
+            }
+            result = result.add_affine(&x, &y);
+        }
+        result
+    }
+}
+
+fn window_digit(scalar: &Integer, window: u32) -> usize {
+    (0..WINDOW_BITS).fold(0, |digit, bit| {
+        digit | (scalar.bit(window * WINDOW_BITS + bit) as usize) << bit
+    })
+}
+
+// Converts all points to affine coordinates with a single field inversion,
+// using Montgomery's trick: invert the product of every Z and peel the
+// individual inverses off with the prefix products.
+fn normalize(
+    curve: &Curve,
+    points: Vec<JacobianPoint>,
+) -> Option<Vec<(FieldElement, FieldElement)>> {
+    if points.iter().any(JacobianPoint::is_infinity) {
+        return None;
+    }
+
+    let coordinates: Vec<_> = points
+        .into_iter()
+        .map(JacobianPoint::into_coordinates)
+        .collect();
+    let one = FieldElement::new_unchecked(Integer::from(1i32), curve.prime().clone());
+
+    let mut prefix = Vec::with_capacity(coordinates.len());
+    let mut product = one.clone();
+    for (_, _, z) in coordinates.iter() {
+        prefix.push(product.clone());
+        product *= z;
+    }
+
+    let mut inverse = one / product;
+    let mut affine = vec![None; coordinates.len()];
+    for (i, (x, y, z)) in coordinates.into_iter().enumerate().rev() {
+        let z_inv = &inverse * &prefix[i];
+        inverse *= z;
+        let z_inv_2 = &z_inv * &z_inv;
+        let z_inv_3 = &z_inv_2 * &z_inv;
+        affine[i] = Some((x * z_inv_2, y * z_inv_3));
+    }
+    affine.into_iter().collect()
+}
+
+impl fmt::Debug for GeneratorTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GeneratorTable({} windows)", self.windows.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_entries() {
+        let curve = Curve::f223();
+        let table = GeneratorTable::new(&curve).unwrap();
+        let g = JacobianPoint::from_affine(&curve.generator());
+
+        // n = 21 has 5 bits, so two windows of 4 bits.
+        assert_eq!(table.windows.len(), 2);
+        for (i, window) in table.windows.iter().enumerate() {
+            for (j, (x, y)) in window.iter().enumerate() {
+                let expected = g.mul(&(Integer::from(j as u32 + 1) << (4 * i as u32)));
+                assert_eq!(Some((x, y)), expected.to_affine().coordinates());
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        for curve in [Curve::f223(), Curve::secp256k1(), Curve::secp256r1()] {
+            let table = GeneratorTable::new(&curve).unwrap();
+            let g = JacobianPoint::from_affine(&curve.generator());
+            let n = curve.order().clone();
+            let mut scalars: Vec<Integer> = (0..40).map(Integer::from).collect();
+            scalars.extend([n.clone() - 1i32, n.clone() + 7i32, -Integer::from(2i32)]);
+            scalars.push(Integer::from(2i32).pow(200) + 98765i32);
+
+            for k in scalars {
+                assert_eq!(
+                    table.mul(&curve, &k).to_affine(),
+                    g.mul(&k.rem_euclid(&n)).to_affine(),
+                    "{}",
+                    k
+                );
+            }
+        }
+    }
+}
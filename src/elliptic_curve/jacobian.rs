@@ -0,0 +1,347 @@
+use std::sync::Arc;
+
+use crate::{
+    finite_field::field_element::FieldElement,
+    integer::{BigInteger, Integer},
+};
+
+use super::{curve::Curve, point::Point};
+
+/// A point in Jacobian coordinates `(X, Y, Z)`, standing for the affine point
+/// `(X / Z^2, Y / Z^3)`. The point at infinity has `Z = 0`.
+///
+/// Additions and doublings need no field inversion, so scalar multiplication
+/// only pays for a single inversion when converting the result back to affine.
+#[derive(Debug, Clone)]
+pub(crate) struct JacobianPoint {
+    curve: Arc<Curve>,
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+}
+
+impl JacobianPoint {
+    pub(crate) fn infinity(curve: &Arc<Curve>) -> JacobianPoint {
+        JacobianPoint {
+            curve: curve.clone(),
+            x: constant(curve, 1),
+            y: constant(curve, 1),
+            z: constant(curve, 0),
+        }
+    }
+
+    pub(crate) fn from_affine(point: &Point) -> JacobianPoint {
+        match point.coordinates() {
+            Some((x, y)) => JacobianPoint {
+                curve: point.curve().clone(),
+                x: x.clone(),
+                y: y.clone(),
+                z: constant(point.curve(), 1),
+            },
+            None => JacobianPoint::infinity(point.curve()),
+        }
+    }
+
+    pub(crate) fn into_coordinates(self) -> (FieldElement, FieldElement, FieldElement) {
+        (self.x, self.y, self.z)
+    }
+
+    pub(crate) fn to_affine(&self) -> Point {
+        if self.is_infinity() {
+            return Point::infinity(&self.curve);
+        }
+
+        let z_inv = constant(&self.curve, 1) / &self.z;
+        let z_inv_2 = &z_inv * &z_inv;
+        let z_inv_3 = &z_inv_2 * &z_inv;
+        Point::from_affine_unchecked(&self.x * &z_inv_2, &self.y * &z_inv_3, &self.curve)
+    }
+
+    pub(crate) fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn neg(&self) -> JacobianPoint {
+        JacobianPoint {
+            curve: self.curve.clone(),
+            x: self.x.clone(),
+            y: -self.y.clone(),
+            z: self.z.clone(),
+        }
+    }
+
+    pub(crate) fn double(&self) -> JacobianPoint {
+        if self.is_infinity() || self.y.is_zero() {
+            return JacobianPoint::infinity(&self.curve);
+        }
+
+        let xx = &self.x * &self.x;
+        let yy = &self.y * &self.y;
+        let yyyy = &yy * &yy;
+        let zz = &self.z * &self.z;
+
+        // S = 4 * X * Y^2, M = 3 * X^2 + a * Z^4
+        let s = times(&self.x * &yy, 4);
+        let m = times(xx, 3) + self.curve.a() * &(&zz * &zz);
+
+        let x = &m * &m - times(s.clone(), 2);
+        let y = m * (s - &x) - times(yyyy, 8);
+        let z = times(&self.y * &self.z, 2);
+
+        JacobianPoint {
+            curve: self.curve.clone(),
+            x,
+            y,
+            z,
+        }
+    }
+
+    pub(crate) fn add(&self, rhs: &JacobianPoint) -> JacobianPoint {
+        if self.is_infinity() {
+            return rhs.clone();
+        } else if rhs.is_infinity() {
+            return self.clone();
+        }
+
+        let z1z1 = &self.z * &self.z;
+        let z2z2 = &rhs.z * &rhs.z;
+        let u1 = &self.x * &z2z2;
+        let u2 = &rhs.x * &z1z1;
+        let s1 = &self.y * &rhs.z * z2z2;
+        let s2 = &rhs.y * &self.z * z1z1;
+
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double()
+            } else {
+                JacobianPoint::infinity(&self.curve)
+            };
+        }
+
+        let h = u2 - &u1;
+        let r = s2 - &s1;
+        let hh = &h * &h;
+        let hhh = &h * &hh;
+        let v = u1 * hh;
+
+        let x = &r * &r - &hhh - times(v.clone(), 2);
+        let y = r * (v - &x) - s1 * hhh;
+        let z = &self.z * &rhs.z * h;
+
+        JacobianPoint {
+            curve: self.curve.clone(),
+            x,
+            y,
+            z,
+        }
+    }
+
+    /// Adds the affine point `(x, y)`, which saves the multiplications by its
+    /// implicit `Z = 1`.
+    pub(crate) fn add_affine(&self, x: &FieldElement, y: &FieldElement) -> JacobianPoint {
+        if self.is_infinity() {
+            return JacobianPoint {
+                curve: self.curve.clone(),
+                x: x.clone(),
+                y: y.clone(),
+                z: constant(&self.curve, 1),
+            };
+        }
+
+        let z1z1 = &self.z * &self.z;
+        let u2 = x * &z1z1;
+        let s2 = y * &self.z * z1z1;
+
+        if self.x == u2 {
+            return if self.y == s2 {
+                self.double()
+            } else {
+                JacobianPoint::infinity(&self.curve)
+            };
+        }
+
+        let h = u2 - &self.x;
+        let r = s2 - &self.y;
+        let hh = &h * &h;
+        let hhh = &h * &hh;
+        let v = &self.x * &hh;
+
+        let x = &r * &r - &hhh - times(v.clone(), 2);
+        let y = r * (v - &x) - &self.y * &hhh;
+        let z = &self.z * &h;
+
+        JacobianPoint {
+            curve: self.curve.clone(),
+            x,
+            y,
+            z,
+        }
+    }
+
+    pub(crate) fn mul(&self, coefficient: &Integer) -> JacobianPoint {
+        let mut result = JacobianPoint::infinity(&self.curve);
+        if *coefficient <= 0 {
+            return result;
+        }
+
+        for bit in (0..coefficient.bits()).rev() {
+            result = result.double();
+            if coefficient.bit(bit) {
+                result = result.add(self);
+            }
+        }
+        result
+    }
+
+    /// Multiplies by a secret scalar with a Montgomery ladder: every bit costs
+    /// one addition and one doubling, and the ladder state is swapped through
+    /// `select` rather than a branch on the bit. The scalar is blinded as
+    /// `k + n` or `k + 2n` so the ladder always runs over `bits(n) + 1` bits,
+    /// which requires `n * self` to be the point at infinity.
+    ///
+    /// This makes the sequence of point operations independent of the scalar,
+    /// but it is not constant-time: `add` and `double` still branch on the
+    /// point at infinity and on equal inputs, and the field and scalar
+    /// arithmetic runs in time that depends on the values, fully so on the
+    /// `Integer` representation.
+    pub(crate) fn mul_ladder(&self, scalar: &Integer) -> JacobianPoint {
+        let n = self.curve.order();
+        let bits = n.bits();
+        let blinded = scalar.rem_euclid(n) + n;
+        let carried = blinded.bit(bits) as i32;
+        let blinded = blinded + n.clone() * (1 - carried);
+
+        // The top bit at index `bits` is always set, so start from (P, 2P).
+        let mut r0 = self.clone();
+        let mut r1 = self.double();
+        for i in (0..bits).rev() {
+            let bit = blinded.bit(i);
+            JacobianPoint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+            JacobianPoint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        r0
+    }
+
+    fn conditional_swap(a: &mut JacobianPoint, b: &mut JacobianPoint, choice: bool) {
+        let swapped_a = JacobianPoint::select(a, b, choice);
+        *b = JacobianPoint::select(b, a, choice);
+        *a = swapped_a;
+    }
+
+    /// Returns `a` when `choice` is false and `b` when it is true, without
+    /// branching on `choice`.
+    pub(crate) fn select(a: &JacobianPoint, b: &JacobianPoint, choice: bool) -> JacobianPoint {
+        JacobianPoint {
+            curve: a.curve.clone(),
+            x: FieldElement::select(&a.x, &b.x, choice),
+            y: FieldElement::select(&a.y, &b.y, choice),
+            z: FieldElement::select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+fn constant(curve: &Curve, value: u32) -> FieldElement {
+    FieldElement::new_unchecked(Integer::from(value) % curve.prime(), curve.prime().clone())
+}
+
+fn times(element: FieldElement, factor: u32) -> FieldElement {
+    match factor {
+        2 => &element + &element,
+        3 => &element + &element + &element,
+        4 => times(times(element, 2), 2),
+        8 => times(times(element, 4), 2),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let curve = Curve::f223();
+        let g = curve.generator();
+
+        assert_eq!(JacobianPoint::from_affine(&g).to_affine(), g);
+        assert_eq!(
+            JacobianPoint::infinity(&curve).to_affine(),
+            Point::infinity(&curve)
+        );
+    }
+
+    #[test]
+    fn test_matches_affine_arithmetic() {
+        for curve in [Curve::f223(), Curve::secp256k1(), Curve::secp256r1()] {
+            let g = curve.generator();
+            let jacobian_g = JacobianPoint::from_affine(&g);
+
+            let mut affine = Point::infinity(&curve);
+            let mut jacobian = JacobianPoint::infinity(&curve);
+            for _ in 0..30 {
+                affine += &g;
+                jacobian = jacobian.add(&jacobian_g);
+                assert_eq!(jacobian.to_affine(), affine);
+            }
+
+            assert_eq!(jacobian_g.double().to_affine(), g.clone() + g.clone());
+            let (gx, gy) = g.coordinates().unwrap();
+            assert_eq!(
+                jacobian.add_affine(gx, gy).to_affine(),
+                affine.clone() + g.clone()
+            );
+            assert_eq!(
+                jacobian_g.add_affine(gx, gy).to_affine(),
+                g.clone() + g.clone()
+            );
+            assert_eq!(
+                JacobianPoint::infinity(&curve)
+                    .add_affine(gx, gy)
+                    .to_affine(),
+                g
+            );
+            assert_eq!(jacobian.add(&jacobian).to_affine(), affine.clone() + affine);
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        let curve = Curve::f223();
+        let g = JacobianPoint::from_affine(&curve.generator());
+
+        assert!(g.mul(curve.order()).is_infinity());
+        assert!(g.mul(&Integer::from(0i32)).is_infinity());
+        // 41 = 20 mod 21
+        assert_eq!(
+            g.mul(&Integer::from(20i32)).to_affine(),
+            g.mul(&Integer::from(41i32)).to_affine()
+        );
+    }
+
+    #[test]
+    fn test_mul_ladder() {
+        for curve in [Curve::f223(), Curve::secp256k1(), Curve::secp256r1()] {
+            let g = JacobianPoint::from_affine(&curve.generator());
+            let n = curve.order().clone();
+            let mut scalars: Vec<Integer> = (0..30).map(Integer::from).collect();
+            scalars.extend([
+                n.clone() - 1i32,
+                n.clone(),
+                n.clone() + 5i32,
+                -Integer::from(3i32),
+            ]);
+            scalars.push(Integer::from(2i32).pow(200) + 12345i32);
+
+            for k in scalars {
+                assert_eq!(
+                    g.mul_ladder(&k).to_affine(),
+                    g.mul(&k.rem_euclid(&n)).to_affine(),
+                    "{}",
+                    k
+                );
+            }
+        }
+    }
+}
@@ -1 +1,6 @@
+pub mod curve;
+mod generator_table;
+mod jacobian;
+mod multi_mul;
 pub mod point;
+pub mod scalar;
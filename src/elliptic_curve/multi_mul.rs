@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use crate::integer::{BigInteger, Integer};
+
+use super::{curve::Curve, jacobian::JacobianPoint};
+
+/// Computes `sum(k_i * P_i)` with Strauss' interleaving: the wNAF digits of all
+/// scalars are walked together, so the doublings are shared by every term and
+/// each term only adds its non-zero digits. Suited to a handful of terms, such
+/// as `u * G + v * P` in signature verification.
+pub(crate) fn strauss(curve: &Arc<Curve>, terms: &[(Integer, JacobianPoint)]) -> JacobianPoint {
+    let expanded: Vec<(Vec<i32>, Vec<JacobianPoint>)> = terms
+        .iter()
+        .map(|(scalar, point)| {
+            let (magnitude, point) = signed(scalar, point);
+            let width = if magnitude.bits() > 64 { 5 } else { 3 };
+            (wnaf(&magnitude, width), odd_multiples(&point, width))
+        })
+        .collect();
+
+    let length = expanded
+        .iter()
+        .map(|(digits, _)| digits.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut result = JacobianPoint::infinity(curve);
+    for i in (0..length).rev() {
+        result = result.double();
+        for (digits, multiples) in expanded.iter() {
+            match digits.get(i) {
+                Some(&digit) if digit > 0 => {
+                    result = result.add(&multiples[digit as usize / 2]);
+                }
+                Some(&digit) if digit < 0 => {
+                    result = result.add(&multiples[(-digit) as usize / 2].neg());
+                }
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+/// Computes `sum(k_i * P_i)` with Pippenger's bucket method. Each window of
+/// `c` bits sorts the points into `2^c - 1` buckets by digit and sums the
+/// buckets with a running total, so the cost per term shrinks as the number of
+/// terms grows. Suited to large batches.
+pub(crate) fn pippenger(curve: &Arc<Curve>, terms: &[(Integer, JacobianPoint)]) -> JacobianPoint {
+    let terms: Vec<(Integer, JacobianPoint)> = terms
+        .iter()
+        .map(|(scalar, point)| signed(scalar, point))
+        .collect();
+    let bits = terms
+        .iter()
+        .map(|(scalar, _)| scalar.bits())
+        .max()
+        .unwrap_or(0);
+    let window = bucket_window(terms.len());
+
+    let mut result = JacobianPoint::infinity(curve);
+    for start in (0..bits.div_ceil(window)).rev().map(|i| i * window) {
+        for _ in 0..window {
+            result = result.double();
+        }
+
+        let mut buckets = vec![JacobianPoint::infinity(curve); (1 << window) - 1];
+        for (scalar, point) in terms.iter() {
+            let digit = (0..window).fold(0, |digit, bit| {
+                digit | (scalar.bit(start + bit) as usize) << bit
+            });
+            if digit > 0 {
+                buckets[digit - 1] = buckets[digit - 1].add(point);
+            }
+        }
+
+        // running = sum of buckets[j..], so the total counts bucket j j + 1 times.
+        let mut running = JacobianPoint::infinity(curve);
+        let mut total = JacobianPoint::infinity(curve);
+        for bucket in buckets.iter().rev() {
+            running = running.add(bucket);
+            total = total.add(&running);
+        }
+        result = result.add(&total);
+    }
+    result
+}
+
+// Turns a possibly negative scalar into its magnitude, negating the point.
+fn signed(scalar: &Integer, point: &JacobianPoint) -> (Integer, JacobianPoint) {
+    if *scalar < 0 {
+        (-scalar.clone(), point.neg())
+    } else {
+        (scalar.clone(), point.clone())
+    }
+}
+
+// Width-`width` non-adjacent form, least significant digit first. Every
+// non-zero digit is odd and below `2^(width - 1)` in absolute value, and any
+// two non-zero digits are at least `width` positions apart.
+fn wnaf(scalar: &Integer, width: u32) -> Vec<i32> {
+    let modulus = 1i32 << width;
+    let mut scalar = scalar.clone();
+    let mut digits = Vec::with_capacity(scalar.bits() as usize + 1);
+    while scalar > 0 {
+        let mut digit = 0;
+        if scalar.is_odd() {
+            digit = scalar.rem_u32(modulus as u32) as i32;
+            if digit >= modulus / 2 {
+                digit -= modulus;
+            }
+            scalar -= digit;
+        }
+        digits.push(digit);
+        scalar >>= 1;
+    }
+    digits
+}
+
+// P, 3P, 5P, ..., (2^(width - 1) - 1)P
+fn odd_multiples(point: &JacobianPoint, width: u32) -> Vec<JacobianPoint> {
+    let double = point.double();
+    let mut multiples = vec![point.clone()];
+    for _ in 1..1 << (width - 2) {
+        let next = multiples.last().unwrap().add(&double);
+        multiples.push(next);
+    }
+    multiples
+}
+
+fn bucket_window(terms: usize) -> u32 {
+    match terms {
+        0..=3 => 2,
+        4..=31 => 3,
+        // Roughly log2(terms) - 1, which balances bucket additions against
+        // the number of windows.
+        _ => (usize::BITS - terms.leading_zeros())
+            .saturating_sub(2)
+            .min(16),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wnaf() {
+        for value in [0u64, 1, 7, 8, 255, 1000, 0xdead_beef, u64::MAX] {
+            for width in 2..=6 {
+                let digits = wnaf(&Integer::from(value), width);
+                let sum = digits
+                    .iter()
+                    .rev()
+                    .fold(Integer::from(0i32), |sum, &digit| sum * 2i32 + digit);
+                assert_eq!(sum, Integer::from(value));
+
+                for (i, &digit) in digits.iter().enumerate() {
+                    if digit != 0 {
+                        assert!(digit % 2 != 0 && digit.abs() < 1 << (width - 1));
+                        let next = digits.iter().skip(i + 1).take(width as usize - 1);
+                        assert!(next.into_iter().all(|&d| d == 0));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_strauss_and_pippenger() {
+        for curve in [Curve::f223(), Curve::secp256k1()] {
+            let g = JacobianPoint::from_affine(&curve.generator());
+            let n = curve.order().clone();
+
+            for count in [1usize, 2, 5, 40] {
+                let terms: Vec<(Integer, JacobianPoint)> = (0..count)
+                    .map(|i| {
+                        let scalar = (n.clone() - 1i32) * (i as i32 + 3) / 7i32 - 5i32;
+                        let point = g.mul(&Integer::from(i as u32 * 13 + 1));
+                        (scalar, point)
+                    })
+                    .collect();
+
+                let expected = terms
+                    .iter()
+                    .fold(JacobianPoint::infinity(&curve), |sum, (scalar, point)| {
+                        sum.add(&point.mul(&scalar.rem_euclid(&n)))
+                    });
+
+                assert_eq!(strauss(&curve, &terms).to_affine(), expected.to_affine());
+                assert_eq!(pippenger(&curve, &terms).to_affine(), expected.to_affine());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cancelling_terms() {
+        let curve = Curve::secp256k1();
+        let g = JacobianPoint::from_affine(&curve.generator());
+        let terms = [
+            (Integer::from(5i32), g.clone()),
+            (Integer::from(-5i32), g.clone()),
+            (Integer::from(0i32), g.double()),
+            (Integer::from(9i32), JacobianPoint::infinity(&curve)),
+        ];
+
+        assert!(strauss(&curve, &terms).is_infinity());
+        assert!(pippenger(&curve, &terms).is_infinity());
+        assert!(strauss(&curve, &[]).is_infinity());
+        assert!(pippenger(&curve, &[]).is_infinity());
+    }
+}
@@ -1,18 +1,25 @@
 use std::{
     fmt,
-    ops::{Add, Mul},
+    ops::{Add, AddAssign, Mul, MulAssign},
+    sync::{Arc, OnceLock},
 };
 
-use rug::{ops::Pow, Integer};
-
-use crate::{cryptography::signature::Signature, finite_field::field_element::FieldElement};
+use crate::{
+    cryptography::{
+        hashing::{hash160, hash256},
+        signature::Signature,
+    },
+    encoding::base58,
+    finite_field::field_element::FieldElement,
+    integer::{BigInteger, Integer},
+    network::Network,
+};
 
-static N: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+use super::{curve::Curve, jacobian::JacobianPoint, multi_mul, scalar::Scalar};
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Point {
-    a: FieldElement,
-    b: FieldElement,
+    curve: Arc<Curve>,
     x: Option<FieldElement>,
     y: Option<FieldElement>,
 }
@@ -20,18 +27,13 @@ pub struct Point {
 #[derive(Debug)]
 pub enum PointError {
     PointNotInCurve(String),
+    InvalidSecEncoding(String),
+    DifferentCurves(String),
 }
 
 impl Point {
-    pub fn new(
-        x: FieldElement,
-        y: FieldElement,
-        a: FieldElement,
-        b: FieldElement,
-    ) -> Result<Point, PointError> {
-        if y.pow(&Integer::from(2i32))
-            != x.pow(&Integer::from(3i32)) + a.clone() * x.clone() + b.clone()
-        {
+    pub fn new(x: FieldElement, y: FieldElement, curve: &Arc<Curve>) -> Result<Point, PointError> {
+        if !curve.contains(&x, &y) {
             return Err(PointError::PointNotInCurve(format!(
                 "({}, {}) is not on the curve",
                 x, y,
@@ -39,227 +41,796 @@ impl Point {
         }
 
         Ok(Point {
-            a,
-            b,
+            curve: curve.clone(),
             x: Some(x),
             y: Some(y),
         })
     }
 
-    pub fn infinity(a: FieldElement, b: FieldElement) -> Point {
+    pub fn infinity(curve: &Arc<Curve>) -> Point {
         Point {
+            curve: curve.clone(),
             x: None,
             y: None,
-            a,
-            b,
         }
     }
 
-    pub fn g_point() -> Point {
-        let gx = Integer::from_str_radix(
-            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
-            16,
-        )
-        .unwrap();
-        let gy = Integer::from_str_radix(
-            "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
-            16,
-        )
-        .unwrap();
-        let p = Integer::from(2i32).pow(256) - Integer::from(2i32).pow(32) - Integer::from(977i32);
-
-        let x = FieldElement::new(gx, p.clone()).unwrap();
-        let y = FieldElement::new(gy, p.clone()).unwrap();
-        let seven = FieldElement::new(Integer::from(7i32), p.clone()).unwrap();
-        let zero = FieldElement::new(Integer::from(0i32), p).unwrap();
-
+    pub(crate) fn from_affine_unchecked(
+        x: FieldElement,
+        y: FieldElement,
+        curve: &Arc<Curve>,
+    ) -> Point {
         Point {
+            curve: curve.clone(),
             x: Some(x),
             y: Some(y),
-            a: zero,
-            b: seven,
         }
     }
 
-    pub fn verify(self, z: Integer, sig: Signature) -> bool {
-        let n = Integer::from_str_radix(N, 16).unwrap();
-        let s_inv = sig
-            .clone()
-            .s()
-            .pow_mod(&(n.clone() - Integer::from(2i32)), &n)
-            .unwrap();
-        let u = z * s_inv.clone() % n.clone();
-        let v = sig.clone().r() * s_inv % n;
-        let total = u * Point::g_point() + v * self;
-        total.x.unwrap().num() == sig.r()
+    pub fn g_point() -> Point {
+        static G: OnceLock<Point> = OnceLock::new();
+        G.get_or_init(|| Curve::secp256k1().generator()).clone()
+    }
+
+    /// Verifies an ECDSA signature of the hash `z` with this point as the
+    /// public key. Returns false, rather than panicking, for a key at infinity,
+    /// for a zero `r` or `s` and for a signature whose scalars are not modulo
+    /// the order of this point's curve.
+    pub fn verify(&self, z: &Integer, sig: &Signature) -> bool {
+        let n = self.curve.order();
+        if self.x.is_none() || sig.r().modulus() != n || sig.s().modulus() != n || sig.r().is_zero()
+        {
+            return false;
+        }
+        let s_inv = match sig.s().invert() {
+            Some(s_inv) => s_inv,
+            None => return false,
+        };
+
+        let u = Scalar::reduce_on_curve(z, &self.curve) * &s_inv;
+        let v = sig.r() * &s_inv;
+        let g = self.curve.generator();
+        let total = Point::multi_mul(&[(u.value().clone(), g), (v.value().clone(), self.clone())]);
+        total
+            .x
+            .is_some_and(|x| Scalar::reduce_on_curve(&x.num(), &self.curve) == *sig.r())
+    }
+
+    /// Verifies a signature of the hash256 digest of `message`.
+    pub fn verify_message(&self, message: &[u8], sig: &Signature) -> bool {
+        self.verify(&Integer::from_be_bytes(&hash256(message)), sig)
+    }
+
+    /// Computes `k_1 * P_1 + ... + k_m * P_m` with interleaved wNAF
+    /// multiplication, which shares the doublings between all terms.
+    ///
+    /// Panics if `terms` is empty or the points are on different curves.
+    pub fn multi_mul(terms: &[(Integer, Point)]) -> Point {
+        let (curve, terms) = jacobian_terms(terms);
+        multi_mul::strauss(&curve, &terms).to_affine()
+    }
+
+    /// Same as `multi_mul`, using Pippenger's bucket method, which is faster
+    /// once there are more than a few dozen terms.
+    ///
+    /// Panics if `terms` is empty or the points are on different curves.
+    pub fn multi_mul_pippenger(terms: &[(Integer, Point)]) -> Point {
+        let (curve, terms) = jacobian_terms(terms);
+        multi_mul::pippenger(&curve, &terms).to_affine()
+    }
+
+    /// Multiplies the point by a secret scalar, such as a private key or a
+    /// signing nonce, with a sequence of point operations that does not depend
+    /// on the scalar. It is not constant-time, see `JacobianPoint::mul_ladder`,
+    /// and only narrows the timing leak of double-and-add. The point
+    /// must lie in the subgroup generated by the curve generator. Public
+    /// scalars should use `scalar * point`, which is faster.
+    pub fn mul_secret(&self, scalar: &Integer) -> Point {
+        JacobianPoint::from_affine(self)
+            .mul_ladder(scalar)
+            .to_affine()
+    }
+
+    /// Adds two points, failing instead of panicking when they are on
+    /// different curves.
+    pub fn checked_add(&self, rhs: &Point) -> Result<Point, PointError> {
+        if !Arc::ptr_eq(&self.curve, &rhs.curve) && self.curve != rhs.curve {
+            return Err(PointError::DifferentCurves(format!(
+                "Points {}, {} are not on the same curve",
+                self, rhs
+            )));
+        }
+
+        let (x1, y1) = match self.coordinates() {
+            Some(coordinates) => coordinates,
+            None => return Ok(rhs.clone()),
+        };
+        let (x2, y2) = match rhs.coordinates() {
+            Some(coordinates) => coordinates,
+            None => return Ok(self.clone()),
+        };
+
+        if x1 == x2 && y1 != y2 {
+            return Ok(Point::infinity(&self.curve));
+        }
+
+        let slope = if x1 == x2 {
+            if y1.is_zero() {
+                return Ok(Point::infinity(&self.curve));
+            }
+            let xx = x1 * x1;
+            (&xx + &xx + &xx + self.curve.a()) / (y1 + y1)
+        } else {
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = &slope * &slope - x1 - x2;
+        let y3 = slope * (x1 - &x3) - y1;
+
+        Ok(Point {
+            curve: self.curve.clone(),
+            x: Some(x3),
+            y: Some(y3),
+        })
+    }
+
+    pub fn curve(&self) -> &Arc<Curve> {
+        &self.curve
     }
 
-    pub fn x(self) -> Option<FieldElement> {
-        self.x
+    pub(crate) fn coordinates(&self) -> Option<(&FieldElement, &FieldElement)> {
+        match (self.x.as_ref(), self.y.as_ref()) {
+            (Some(x), Some(y)) => Some((x, y)),
+            _ => None,
+        }
     }
+
+    pub fn x(&self) -> Option<&FieldElement> {
+        self.x.as_ref()
+    }
+
+    pub fn y(&self) -> Option<&FieldElement> {
+        self.y.as_ref()
+    }
+
+    /// Serializes the point in SEC1 format: `0x04 || x || y` when uncompressed,
+    /// `0x02 || x` or `0x03 || x` (for even and odd `y`) when compressed. The
+    /// point at infinity is encoded as the single byte `0x00`.
+    pub fn sec(&self, compressed: bool) -> Vec<u8> {
+        let (x, y) = match (self.x.as_ref(), self.y.as_ref()) {
+            (Some(x), Some(y)) => (x.num(), y.num()),
+            _ => return vec![0x00],
+        };
+        let len = coordinate_len(self.curve.prime());
+
+        let mut sec = Vec::with_capacity(1 + 2 * len);
+        if compressed {
+            sec.push(if y.is_even() { 0x02 } else { 0x03 });
+            sec.extend(to_be_bytes(&x, len));
+        } else {
+            sec.push(0x04);
+            sec.extend(to_be_bytes(&x, len));
+            sec.extend(to_be_bytes(&y, len));
+        }
+        sec
+    }
+
+    /// hash160 of the SEC encoding of the point.
+    pub fn hash160(&self, compressed: bool) -> [u8; 20] {
+        hash160(&self.sec(compressed))
+    }
+
+    /// Legacy P2PKH address of the point as a public key.
+    pub fn address(&self, compressed: bool, network: Network) -> String {
+        let mut payload = vec![network.p2pkh_prefix()];
+        payload.extend_from_slice(&self.hash160(compressed));
+        base58::encode_check(&payload)
+    }
+
+    /// Parses a SEC1 encoded secp256k1 point, recovering `y` from `x` for
+    /// compressed encodings.
+    pub fn parse_sec(sec: &[u8]) -> Result<Point, PointError> {
+        Point::parse_sec_on_curve(sec, &Curve::secp256k1())
+    }
+
+    pub fn parse_sec_on_curve(sec: &[u8], curve: &Arc<Curve>) -> Result<Point, PointError> {
+        let prime = curve.prime();
+        let len = coordinate_len(prime);
+
+        match (sec.first(), sec.len()) {
+            (Some(0x00), 1) => Ok(Point::infinity(curve)),
+            (Some(0x04), l) if l == 1 + 2 * len => {
+                let x = sec_coordinate(&sec[1..1 + len], prime)?;
+                let y = sec_coordinate(&sec[1 + len..], prime)?;
+                Point::new(x, y, curve)
+            }
+            (Some(prefix @ (0x02 | 0x03)), l) if l == 1 + len => {
+                let x = sec_coordinate(&sec[1..], prime)?;
+                let alpha = x.pow(&Integer::from(3i32)) + curve.a() * &x + curve.b();
+                let beta = alpha.sqrt().ok_or_else(|| {
+                    PointError::PointNotInCurve(format!("no point on the curve with x = {}", x))
+                })?;
+
+                let y = if beta.num().is_even() == (*prefix == 0x02) {
+                    beta
+                } else {
+                    -beta
+                };
+                Point::new(x, y, curve)
+            }
+            _ => Err(PointError::InvalidSecEncoding(format!(
+                "{} bytes with prefix {:?} is not a valid SEC encoding",
+                sec.len(),
+                sec.first()
+            ))),
+        }
+    }
+}
+
+fn jacobian_terms(terms: &[(Integer, Point)]) -> (Arc<Curve>, Vec<(Integer, JacobianPoint)>) {
+    let curve = terms
+        .first()
+        .expect("Cannot multiply an empty list of points")
+        .1
+        .curve
+        .clone();
+    let terms = terms
+        .iter()
+        .map(|(scalar, point)| {
+            assert!(
+                Arc::ptr_eq(&curve, &point.curve) || curve == point.curve,
+                "Cannot multiply points on different curves"
+            );
+            (scalar.clone(), JacobianPoint::from_affine(point))
+        })
+        .collect();
+    (curve, terms)
+}
+
+fn coordinate_len(prime: &Integer) -> usize {
+    (prime.bits() as usize).div_ceil(8)
+}
+
+fn to_be_bytes(value: &Integer, len: usize) -> Vec<u8> {
+    let digits = value.to_be_bytes();
+    let mut bytes = vec![0u8; len - digits.len()];
+    bytes.extend(digits);
+    bytes
+}
+
+// The field order was validated when the curve was built, so only the range
+// of the coordinate needs checking.
+fn sec_coordinate(bytes: &[u8], prime: &Integer) -> Result<FieldElement, PointError> {
+    let num = Integer::from_be_bytes(bytes);
+    if num >= *prime {
+        return Err(PointError::InvalidSecEncoding(format!(
+            "Coordinate {} not in field range 0 to {}",
+            num,
+            prime.clone() - 1i32
+        )));
+    }
+    Ok(FieldElement::new_unchecked(num, prime.clone()))
 }
 
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Point({}, {})_{}_{}",
-            self.x.clone().unwrap(),
-            self.y.clone().unwrap(),
-            self.a,
-            self.b
-        )
+        match self.coordinates() {
+            Some((x, y)) => write!(
+                f,
+                "Point({}, {})_{}_{}",
+                x,
+                y,
+                self.curve.a(),
+                self.curve.b()
+            ),
+            None => write!(f, "Point(infinity)_{}_{}", self.curve.a(), self.curve.b()),
+        }
+    }
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointError::PointNotInCurve(message)
+            | PointError::InvalidSecEncoding(message)
+            | PointError::DifferentCurves(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PointError {}
+
+impl Add<&Point> for &Point {
+    type Output = Point;
+
+    fn add(self, rhs: &Point) -> Point {
+        self.checked_add(rhs).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 impl Add for Point {
     type Output = Point;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        if self.x.is_none() {
-            return rhs;
-        } else if rhs.x.is_none() {
-            return self;
-        }
+    fn add(self, rhs: Point) -> Point {
+        &self + &rhs
+    }
+}
 
-        match (
-            self.x.as_ref(),
-            self.y.as_ref(),
-            rhs.x.as_ref(),
-            rhs.y.as_ref(),
-        ) {
-            (Some(x1), Some(y1), Some(x2), Some(y2)) => {
-                let x1 = x1.clone();
-                let y1 = y1.clone();
-                let x2 = x2.clone();
-                let y2 = y2.clone();
-
-                if self.a != rhs.a || self.b != rhs.b {
-                    panic!(
-                        "{}",
-                        format!("Points {:?}, {:?} are not on the same curve.", self, rhs)
-                    );
-                }
-
-                let x_sum;
-                let y_sum;
-                if self == rhs {
-                    if y1 == FieldElement::new(Integer::from(0i32), y1.clone().prime()).unwrap() {
-                        return Point {
-                            x: None,
-                            y: None,
-                            a: self.a,
-                            b: self.b,
-                        };
-                    }
-                    let slope = (FieldElement::new(Integer::from(3i32), self.a.clone().prime())
-                        .unwrap()
-                        * x1.pow(&Integer::from(2i32))
-                        + self.a.clone())
-                        / (FieldElement::new(Integer::from(2i32), self.a.clone().prime()).unwrap()
-                            * y1.clone());
-                    x_sum = slope.pow(&Integer::from(2i32)) - x1.clone() - x2;
-                    y_sum = slope * (x1 - x_sum.clone()) - y1;
-                } else {
-                    let slope = (y2 - y1.clone()) / (x2.clone() - x1.clone());
-                    x_sum = slope.pow(&Integer::from(2i32)) - x1.clone() - x2;
-                    y_sum = slope * (x1 - x_sum.clone()) - y1;
-                }
-
-                Point {
-                    a: self.a,
-                    b: self.b,
-                    x: Some(x_sum),
-                    y: Some(y_sum),
-                }
-            }
+impl Add<&Point> for Point {
+    type Output = Point;
 
-            _ => Point::infinity(self.a, self.b),
-        }
+    fn add(self, rhs: &Point) -> Point {
+        &self + rhs
+    }
+}
+
+impl Add<Point> for &Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        self + &rhs
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = &*self + &rhs;
+    }
+}
+
+impl AddAssign<&Point> for Point {
+    fn add_assign(&mut self, rhs: &Point) {
+        *self = &*self + rhs;
+    }
+}
+
+impl Mul<&Point> for &Integer {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        JacobianPoint::from_affine(point).mul(self).to_affine()
     }
 }
 
 impl Mul<Point> for Integer {
     type Output = Point;
 
-    fn mul(self, point: Point) -> Self::Output {
-        let mut coef = self;
-        let mut current = point.clone();
-        let mut result = Point::infinity(point.a, point.b);
+    fn mul(self, point: Point) -> Point {
+        &self * &point
+    }
+}
 
-        while coef > 0 {
-            if coef.is_odd() {
-                result = result + current.clone(); // TODO: fix when MulAssign is implemented
-            }
-            current = current.clone() + current; // TODO: fix when AddAssign is implemented
-            coef >>= 1;
-        }
-        result
+impl Mul<&Point> for Integer {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        &self * point
+    }
+}
+
+impl Mul<Point> for &Integer {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        self * &point
+    }
+}
+
+impl Mul<&Point> for &Scalar {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        self.value() * point
+    }
+}
+
+impl Mul<Point> for Scalar {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        &self * &point
+    }
+}
+
+impl Mul<&Point> for Scalar {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        &self * point
+    }
+}
+
+impl Mul<Point> for &Scalar {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        self * &point
+    }
+}
+
+impl MulAssign<Integer> for Point {
+    fn mul_assign(&mut self, coefficient: Integer) {
+        *self = &coefficient * &*self;
+    }
+}
+
+impl MulAssign<&Integer> for Point {
+    fn mul_assign(&mut self, coefficient: &Integer) {
+        *self = coefficient * &*self;
+    }
+}
+
+impl MulAssign<&Scalar> for Point {
+    fn mul_assign(&mut self, coefficient: &Scalar) {
+        *self = coefficient * &*self;
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::cryptography::{hashing::sha256, private_key::PrivateKey};
+
     use super::*;
 
     #[test]
     fn test_on_curve() {
-        let prime = 223;
-        let a = 0;
-        let b = 7;
+        let curve = Curve::f223();
 
-        assert!(point(192, 105, a, b, prime).is_ok());
-        assert!(point(17, 56, a, b, prime).is_ok());
-        assert!(point(200, 119, a, b, prime).is_err());
-        assert!(point(1, 193, a, b, prime).is_ok());
-        assert!(point(42, 99, a, b, prime).is_err());
+        assert!(point(192, 105, &curve).is_ok());
+        assert!(point(17, 56, &curve).is_ok());
+        assert!(point(200, 119, &curve).is_err());
+        assert!(point(1, 193, &curve).is_ok());
+        assert!(point(42, 99, &curve).is_err());
     }
 
     #[test]
     fn test_add() {
-        let prime = 223;
-        let a = 0;
-        let b = 7;
+        let curve = Curve::f223();
 
-        let p1 = point(192, 105, a, b, prime).unwrap();
-        let p2 = point(17, 56, a, b, prime).unwrap();
-        let p3 = point(170, 142, a, b, prime).unwrap();
+        let p1 = point(192, 105, &curve).unwrap();
+        let p2 = point(17, 56, &curve).unwrap();
+        let p3 = point(170, 142, &curve).unwrap();
 
         assert_eq!(p1 + p2, p3);
 
-        let p1 = point(47, 71, a, b, prime).unwrap();
-        let p2 = point(117, 141, a, b, prime).unwrap();
-        let p3 = point(60, 139, a, b, prime).unwrap();
+        let p1 = point(47, 71, &curve).unwrap();
+        let p2 = point(117, 141, &curve).unwrap();
+        let p3 = point(60, 139, &curve).unwrap();
 
         assert_eq!(p1 + p2, p3);
 
-        let p1 = point(143, 98, a, b, prime).unwrap();
-        let p2 = point(76, 66, a, b, prime).unwrap();
-        let p3 = point(47, 71, a, b, prime).unwrap();
+        let p1 = point(143, 98, &curve).unwrap();
+        let p2 = point(76, 66, &curve).unwrap();
+        let p3 = point(47, 71, &curve).unwrap();
 
         assert_eq!(p1 + p2, p3);
     }
 
     #[test]
     fn test_mul() {
-        let prime = 223;
-        let a = 0;
-        let b = 7;
+        let curve = Curve::f223();
 
-        let p1 = point(170, 142, a, b, prime).unwrap();
-        assert_eq!(p1.clone() + p1.clone(), Integer::from(2) * p1.clone());
+        let p1 = point(170, 142, &curve).unwrap();
+        assert_eq!(p1.clone() + p1.clone(), Integer::from(2i32) * p1.clone());
         assert_eq!(
-            Integer::from(2) * p1.clone() + p1.clone(),
-            Integer::from(3) * p1
+            Integer::from(2i32) * p1.clone() + p1.clone(),
+            Integer::from(3i32) * p1
         );
     }
 
-    fn point(x: i128, y: i128, a: i128, b: i128, prime: i128) -> Result<Point, PointError> {
+    #[test]
+    fn test_reference_operators() {
+        let curve = Curve::f223();
+        let p1 = point(170, 142, &curve).unwrap();
+        let p2 = point(60, 139, &curve).unwrap();
+        let k = Integer::from(7i32);
+
+        assert_eq!(&p1 + &p2, p1.clone() + p2.clone());
+        assert_eq!(&k * &p1, k.clone() * p1.clone());
+        assert_eq!(&k * p1.clone(), k.clone() * &p1);
+
+        let mut sum = p1.clone();
+        sum += &p2;
+        sum += Point::infinity(&curve);
+        assert_eq!(sum, &p1 + &p2);
+
+        let mut product = p1.clone();
+        product *= &k;
+        product *= Integer::from(3i32);
+        assert_eq!(product, Integer::from(21i32) * &p1);
+        assert_eq!(p1.x().unwrap().num(), 170i32);
+        assert_eq!(p1.y().unwrap().num(), 142i32);
+        assert_eq!(Point::infinity(&curve).x(), None);
+    }
+
+    #[test]
+    fn test_scalar_mul() {
+        let g = Point::g_point();
+        let k = Scalar::from(12345u32);
+        let minus_one = -Scalar::from(1u32);
+
+        assert_eq!(&k * &g, Integer::from(12345i32) * &g);
+        assert_eq!(
+            minus_one.clone() * g.clone() + &g,
+            Point::infinity(g.curve())
+        );
+
+        let mut product = g.clone();
+        product *= &k;
+        assert_eq!(product, k * g);
+    }
+
+    #[test]
+    fn test_multi_mul() {
+        let curve = Curve::secp256k1();
+        let g = curve.generator();
+        let p = Integer::from(5001i32) * g.clone();
+        let terms = [
+            (Integer::from(2i32).pow(255) + 3i32, g.clone()),
+            (Integer::from(-77i32), p.clone()),
+            (curve.order().clone() - 1i32, p.clone()),
+        ];
+        let expected = terms
+            .iter()
+            .fold(Point::infinity(&curve), |sum, (scalar, point)| {
+                sum + scalar.rem_euclid(curve.order()) * point.clone()
+            });
+
+        assert_eq!(Point::multi_mul(&terms), expected);
+        assert_eq!(Point::multi_mul_pippenger(&terms), expected);
+        assert_eq!(
+            Point::multi_mul(&[(Integer::from(7i32), Point::infinity(&curve))]),
+            Point::infinity(&curve)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot multiply points on different curves")]
+    fn test_multi_mul_different_curves() {
+        let secp256k1 = Curve::secp256k1();
+        let f223 = Curve::f223();
+        Point::multi_mul(&[
+            (Integer::from(1i32), secp256k1.generator()),
+            (Integer::from(1i32), f223.generator()),
+        ]);
+    }
+
+    #[test]
+    fn test_sec() {
+        let vectors = [
+            (Integer::from(5000i32), false, "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10"),
+            (Integer::from(2018i32).pow(5), false, "04027f3da1918455e03c46f659266a1bb5204e959db7364d2f473bdf8f0a13cc9dff87647fd023c13b4a4994f17691895806e1b40b57f4fd22581a4f46851f3b06"),
+            (Integer::from(0xdeadbeef12345i64), false, "04d90cd625ee87dd38656dd95cf79f65f60f7273b67d3096e68bd81e4f5342691f842efa762fd59961d0e99803c61edba8b3e3f7dc3a341836f97733aebf987121"),
+            (Integer::from(5001i32), true, "0357a4f368868a8a6d572991e484e664810ff14c05c0fa023275251151fe0e53d1"),
+            (Integer::from(2019i32).pow(5), true, "02933ec2d2b111b92737ec12f1c5d20f3233a0ad21cd8b36d0bca7a0cfa5cb8701"),
+            (Integer::from(0xdeadbeef54321i64), true, "0296be5b1292f6c856b3c5654e886fc13511462059089cdf9c479623bfcbe77690"),
+        ];
+
+        for (secret, compressed, expected) in vectors {
+            let point = secret * Point::g_point();
+            let sec = point.sec(compressed);
+
+            assert_eq!(hex::encode(&sec), expected);
+            assert_eq!(Point::parse_sec(&sec).unwrap(), point);
+        }
+    }
+
+    #[test]
+    fn test_parse_sec_rejects_invalid_input() {
+        let point = Integer::from(5001i32) * Point::g_point();
+
+        let sec = point.sec(true);
+        assert!(Point::parse_sec(&sec[..32]).is_err());
+        assert!(Point::parse_sec(&[]).is_err());
+
+        let mut sec = point.sec(false);
+        sec[0] = 0x05;
+        assert!(Point::parse_sec(&sec).is_err());
+
+        let mut sec = point.sec(false);
+        sec[64] ^= 1;
+        assert!(Point::parse_sec(&sec).is_err());
+
+        // x = 5 has no matching y on secp256k1.
+        let mut sec = vec![0x02];
+        sec.extend([0u8; 31]);
+        sec.push(5);
+        assert!(Point::parse_sec(&sec).is_err());
+
+        let infinity = Point::infinity(point.curve());
+        assert_eq!(Point::parse_sec(&infinity.sec(true)).unwrap(), infinity);
+    }
+
+    #[test]
+    fn test_sec_on_other_curves() {
+        let curve = Curve::secp256r1();
+        let point = Integer::from(12345i32) * curve.generator();
+
+        for compressed in [true, false] {
+            let sec = point.sec(compressed);
+            assert_eq!(Point::parse_sec_on_curve(&sec, &curve).unwrap(), point);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_different_curves() {
+        let _ = Curve::f223().generator() + Curve::secp256k1().generator();
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let curve = Curve::f223();
+        let p1 = point(192, 105, &curve).unwrap();
+        let p2 = point(17, 56, &curve).unwrap();
+
+        assert_eq!(
+            p1.checked_add(&p2).unwrap(),
+            point(170, 142, &curve).unwrap()
+        );
+        assert!(matches!(
+            p1.checked_add(&Curve::secp256k1().generator()),
+            Err(PointError::DifferentCurves(_))
+        ));
+        assert!(matches!(
+            Point::infinity(&curve).checked_add(&Point::infinity(&Curve::secp256k1())),
+            Err(PointError::DifferentCurves(_))
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        let curve = Curve::f223();
+
+        assert_eq!(
+            point(192, 105, &curve).unwrap().to_string(),
+            "Point(FieldElement_223(192), FieldElement_223(105))_FieldElement_223(0)_FieldElement_223(7)"
+        );
+        assert_eq!(
+            Point::infinity(&curve).to_string(),
+            "Point(infinity)_FieldElement_223(0)_FieldElement_223(7)"
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_invalid_keys() {
+        let pk = PrivateKey::new(Scalar::from(12345u32)).unwrap();
+        let z = Integer::from(67890i32);
+        let sig = pk.sign(&z);
+
+        assert!(pk.point().verify(&z, &sig));
+        assert!(!Point::infinity(pk.point().curve()).verify(&z, &sig));
+        assert!(!Curve::f223().generator().verify(&z, &sig));
+        assert!(!pk
+            .point()
+            .verify(&z, &Signature::new(Scalar::from(0u32), sig.s().clone())));
+        assert!(!pk
+            .point()
+            .verify(&z, &Signature::new(sig.r().clone(), Scalar::from(0u32))));
+    }
+
+    // Signed by OpenSSL with the P-256 private key 12345 over the SHA-256 of
+    // "Programming Bitcoin!".
+    #[test]
+    fn test_verify_secp256r1() {
+        let curve = Curve::secp256r1();
+        let point = Point::parse_sec_on_curve(
+            &hex::decode("0226efcebd0ee9e34a669187e18b3a9122b2f733945b649cc9f9f921e9f9dad812")
+                .unwrap(),
+            &curve,
+        )
+        .unwrap();
+        assert_eq!(point, curve.mul_generator(&Integer::from(12345i32)));
+
+        let integer = |value: &str| Integer::from_radix_str(value, 16).unwrap();
+        let z = integer("88f8cba5f9f85d648f82e794c11bdce98e6c54345b93e2840ca2f457116be9da");
+        let scalar = |value: &str| Scalar::new_on_curve(integer(value), &curve).unwrap();
+        let sig = Signature::new(
+            scalar("36d07112e67c7364a1242d2805c1edb25097a3fadf82707ba06265c41e56a1f4"),
+            scalar("608a2a724d0695a0047836d1e962e5ce4325070818f2d8a7be00dbaf95ca5268"),
+        );
+
+        assert!(point.verify(&z, &sig));
+        assert!(!point.verify(&(z.clone() + 1i32), &sig));
+        assert!(!Point::g_point().verify(&z, &sig));
+
+        // The same values modulo the secp256k1 order are a different signature.
+        let secp256k1_sig = Signature::new(
+            Scalar::new(sig.r().value().clone()).unwrap(),
+            Scalar::new(sig.s().value().clone()).unwrap(),
+        );
+        assert!(!point.verify(&z, &secp256k1_sig));
+        assert!(Scalar::new_on_curve(curve.order().clone(), &curve).is_err());
+    }
+
+    #[test]
+    fn test_address() {
+        let cases = [
+            (
+                Integer::from(5002i32),
+                false,
+                Network::Testnet,
+                "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA",
+            ),
+            (
+                Integer::from(2020i32).pow(5),
+                true,
+                Network::Testnet,
+                "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH",
+            ),
+            (
+                Integer::from(0x12345deadbeefi64),
+                true,
+                Network::Mainnet,
+                "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1",
+            ),
+        ];
+
+        for (secret, compressed, network, address) in cases {
+            let pk = PrivateKey::new(Scalar::new(secret).unwrap()).unwrap();
+            assert_eq!(pk.point().address(compressed, network), address);
+        }
+
+        let g = Point::g_point();
+        assert_eq!(
+            hex::encode(g.hash160(true)),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        assert_eq!(
+            g.address(true, Network::Regtest),
+            g.address(true, Network::Signet)
+        );
+    }
+
+    // The Project Wycheproof vectors for ECDSA over secp256k1 with SHA-256,
+    // testvectors_v1/ecdsa_secp256k1_sha256_test.json (generator 0.9rc5).
+    #[test]
+    fn test_verify_wycheproof_vectors() {
+        let vectors: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/ecdsa_secp256k1_sha256_test.json"
+        )))
+        .unwrap();
+
+        let mut count = 0;
+        for group in vectors["testGroups"].as_array().unwrap() {
+            assert_eq!(group["type"], "EcdsaVerify");
+            assert_eq!(group["sha"], "SHA-256");
+            let key = &group["publicKey"];
+            assert_eq!(key["curve"], "secp256k1");
+            let key =
+                Point::parse_sec(&hex::decode(key["uncompressed"].as_str().unwrap()).unwrap())
+                    .unwrap();
+
+            for test in group["tests"].as_array().unwrap() {
+                let msg = hex::decode(test["msg"].as_str().unwrap()).unwrap();
+                let z = Integer::from_be_bytes(&sha256(&msg));
+                let der = hex::decode(test["sig"].as_str().unwrap()).unwrap();
+
+                let valid = match Signature::parse_der(&der) {
+                    Ok(sig) => {
+                        assert_eq!(sig.der(), der, "tcId {}", test["tcId"]);
+                        key.verify(&z, &sig)
+                    }
+                    Err(_) => false,
+                };
+                assert_eq!(
+                    valid,
+                    test["result"] == "valid",
+                    "tcId {}: {}",
+                    test["tcId"],
+                    test["comment"]
+                );
+                count += 1;
+            }
+        }
+        assert_eq!(vectors["numberOfTests"], count);
+    }
+
+    fn point(x: i128, y: i128, curve: &Arc<Curve>) -> Result<Point, PointError> {
         Point::new(
-            FieldElement::new(Integer::from(x), Integer::from(prime)).unwrap(),
-            FieldElement::new(Integer::from(y), Integer::from(prime)).unwrap(),
-            FieldElement::new(Integer::from(a), Integer::from(prime)).unwrap(),
-            FieldElement::new(Integer::from(b), Integer::from(prime)).unwrap(),
+            FieldElement::new(Integer::from(x), curve.prime().clone()).unwrap(),
+            FieldElement::new(Integer::from(y), curve.prime().clone()).unwrap(),
+            curve,
         )
     }
 }
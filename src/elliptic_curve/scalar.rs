@@ -0,0 +1,336 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    sync::{Arc, OnceLock},
+};
+
+use crate::integer::{BigInteger, Integer};
+
+use super::curve::Curve;
+
+/// An integer modulo the order `n` of the group of a curve, always kept in
+/// `0..n`. Private keys, nonces and signature values are scalars. Unless
+/// built with one of the `_on_curve` constructors, the curve is secp256k1.
+///
+/// Arithmetic between scalars of different orders panics.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Scalar {
+    value: Integer,
+    n: Arc<Integer>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ScalarError {
+    ScalarRangeError(String),
+}
+
+impl Scalar {
+    pub fn new(value: Integer) -> Result<Scalar, ScalarError> {
+        Scalar::with_order(value, secp256k1_order())
+    }
+
+    /// Same as `new`, modulo the group order of `curve`.
+    pub fn new_on_curve(value: Integer, curve: &Curve) -> Result<Scalar, ScalarError> {
+        Scalar::with_order(value, curve.shared_order())
+    }
+
+    /// `value` reduced modulo `n`, for inputs such as message hashes that may
+    /// exceed the group order.
+    pub fn reduce(value: &Integer) -> Scalar {
+        Scalar::reduce_on_curve(value, &Curve::secp256k1())
+    }
+
+    /// Same as `reduce`, modulo the group order of `curve`.
+    pub fn reduce_on_curve(value: &Integer, curve: &Curve) -> Scalar {
+        let n = curve.shared_order();
+        Scalar {
+            value: value.rem_euclid(n),
+            n: n.clone(),
+        }
+    }
+
+    /// The order `n` of the secp256k1 group.
+    pub fn order() -> &'static Integer {
+        secp256k1_order()
+    }
+
+    /// The order `n` this scalar is taken modulo.
+    pub fn modulus(&self) -> &Integer {
+        &self.n
+    }
+
+    pub fn value(&self) -> &Integer {
+        &self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /// Whether the scalar is above `n / 2`, the range BIP62 forbids for the
+    /// `s` value of a signature.
+    pub fn is_high(&self) -> bool {
+        self.value > (*self.n).clone() >> 1
+    }
+
+    /// The multiplicative inverse, `None` for zero or, with a composite
+    /// order, for any value not coprime to it.
+    pub fn invert(&self) -> Option<Scalar> {
+        self.value.mod_inverse(&self.n).map(|value| Scalar {
+            value,
+            n: self.n.clone(),
+        })
+    }
+
+    fn with_order(value: Integer, n: &Arc<Integer>) -> Result<Scalar, ScalarError> {
+        if value < 0 || value >= **n {
+            return Err(ScalarError::ScalarRangeError(format!(
+                "Scalar {} not in range 0 to n - 1",
+                value
+            )));
+        }
+
+        Ok(Scalar {
+            value,
+            n: n.clone(),
+        })
+    }
+
+    // The shared order of two operands, which must be equal.
+    fn common_order(&self, rhs: &Scalar) -> &Arc<Integer> {
+        assert!(
+            Arc::ptr_eq(&self.n, &rhs.n) || self.n == rhs.n,
+            "Scalars modulo {} and {} cannot be combined",
+            self.n,
+            rhs.n
+        );
+        &self.n
+    }
+}
+
+fn secp256k1_order() -> &'static Arc<Integer> {
+    static ORDER: OnceLock<Arc<Integer>> = OnceLock::new();
+    ORDER.get_or_init(|| Curve::secp256k1().shared_order().clone())
+}
+
+impl From<u32> for Scalar {
+    fn from(value: u32) -> Scalar {
+        Scalar {
+            value: Integer::from(value),
+            n: secp256k1_order().clone(),
+        }
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl fmt::Display for ScalarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScalarError::ScalarRangeError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScalarError {}
+
+impl Add<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, rhs: &Scalar) -> Scalar {
+        let n = self.common_order(rhs);
+        let mut value = self.value.clone() + &rhs.value;
+        if value >= **n {
+            value -= &**n;
+        }
+        Scalar {
+            value,
+            n: n.clone(),
+        }
+    }
+}
+
+impl Sub<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn sub(self, rhs: &Scalar) -> Scalar {
+        let n = self.common_order(rhs);
+        let mut value = self.value.clone() - &rhs.value;
+        if value < 0 {
+            value += &**n;
+        }
+        Scalar {
+            value,
+            n: n.clone(),
+        }
+    }
+}
+
+impl Mul<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, rhs: &Scalar) -> Scalar {
+        let n = self.common_order(rhs);
+        Scalar {
+            value: (self.value.clone() * &rhs.value) % &**n,
+            n: n.clone(),
+        }
+    }
+}
+
+// Owned and mixed operands, and the assigning operators, forward to the
+// implementations on references above.
+macro_rules! forward_binary_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident) => {
+        impl $op for Scalar {
+            type Output = Scalar;
+
+            fn $method(self, rhs: Scalar) -> Scalar {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $op<&Scalar> for Scalar {
+            type Output = Scalar;
+
+            fn $method(self, rhs: &Scalar) -> Scalar {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $op<Scalar> for &Scalar {
+            type Output = Scalar;
+
+            fn $method(self, rhs: Scalar) -> Scalar {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $assign for Scalar {
+            fn $assign_method(&mut self, rhs: Scalar) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $assign<&Scalar> for Scalar {
+            fn $assign_method(&mut self, rhs: &Scalar) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    };
+}
+
+forward_binary_op!(Add, add, AddAssign, add_assign);
+forward_binary_op!(Sub, sub, SubAssign, sub_assign);
+forward_binary_op!(Mul, mul, MulAssign, mul_assign);
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        let zero = Scalar {
+            value: Integer::from(0i32),
+            n: self.n.clone(),
+        };
+        &zero - self
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let n = Scalar::order().clone();
+
+        assert!(Scalar::new(Integer::from(0i32)).is_ok());
+        assert!(Scalar::new(n.clone() - 1i32).is_ok());
+        assert!(matches!(
+            Scalar::new(n.clone()),
+            Err(ScalarError::ScalarRangeError(_))
+        ));
+        assert!(matches!(
+            Scalar::new(Integer::from(-1i32)),
+            Err(ScalarError::ScalarRangeError(_))
+        ));
+        assert_eq!(Scalar::reduce(&(n.clone() + 5i32)), Scalar::from(5u32));
+        assert_eq!(
+            Scalar::reduce(&Integer::from(-1i32)),
+            Scalar::new(n - 1i32).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let n = Scalar::order().clone();
+        let max = Scalar::new(n.clone() - 1i32).unwrap();
+        let two = Scalar::from(2u32);
+
+        assert_eq!(&max + &two, Scalar::from(1u32));
+        assert_eq!(&two - &max, Scalar::from(3u32));
+        assert_eq!(&max * &max, Scalar::from(1u32));
+        assert_eq!(-&two, Scalar::new(n.clone() - 2i32).unwrap());
+        assert_eq!(-Scalar::from(0u32), Scalar::from(0u32));
+
+        let mut a = two.clone();
+        a += &max;
+        a -= Scalar::from(1u32);
+        a *= &two;
+        assert_eq!(a, Scalar::from(0u32));
+    }
+
+    #[test]
+    fn test_invert() {
+        let a = Scalar::reduce(&Integer::from(2i32).pow(200));
+
+        assert_eq!(a.invert().unwrap() * &a, Scalar::from(1u32));
+        assert_eq!(Scalar::from(0u32).invert(), None);
+    }
+
+    #[test]
+    fn test_is_high() {
+        let half = Scalar::new(Scalar::order().clone() >> 1).unwrap();
+
+        assert!(!half.is_high());
+        assert!((&half + &Scalar::from(1u32)).is_high());
+        assert!(!(-(&half + &Scalar::from(1u32))).is_high());
+    }
+
+    #[test]
+    fn test_on_curve() {
+        let curve = Curve::f223();
+        let a = Scalar::new_on_curve(Integer::from(20i32), &curve).unwrap();
+        let b = Scalar::reduce_on_curve(&Integer::from(23i32), &curve);
+
+        assert_eq!(a.modulus(), &Integer::from(21i32));
+        assert_eq!(b.value(), &Integer::from(2i32));
+        assert_eq!((&a + &b).value(), &Integer::from(1i32));
+        assert_eq!((&a * &b).value(), &Integer::from(19i32));
+        assert!(Scalar::new_on_curve(Integer::from(21i32), &curve).is_err());
+        // 21 is not prime, so 3 has no inverse.
+        assert_eq!(
+            Scalar::reduce_on_curve(&Integer::from(3i32), &curve).invert(),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be combined")]
+    fn test_mixed_orders() {
+        let a = Scalar::reduce_on_curve(&Integer::from(1i32), &Curve::f223());
+        let _ = &a + &Scalar::from(1u32);
+    }
+}
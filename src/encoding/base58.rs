@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::cryptography::hashing::hash256;
+use crate::integer::{BigInteger, Integer};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(PartialEq, Debug)]
+pub enum Base58Error {
+    InvalidCharacter(String),
+    InvalidLength(String),
+    InvalidChecksum(String),
+}
+
+/// Encodes `data` in base58, writing each leading zero byte as a `1`.
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    let mut num = Integer::from_be_bytes(data);
+
+    let mut digits = Vec::new();
+    while num > 0 {
+        digits.push(ALPHABET[num.rem_u32(58) as usize]);
+        num /= 58i32;
+    }
+    digits.extend(std::iter::repeat_n(b'1', zeros));
+    digits.reverse();
+
+    String::from_utf8(digits).expect("the alphabet is ASCII")
+}
+
+/// Encodes `data` followed by the first four bytes of its hash256 digest.
+pub fn encode_check(data: &[u8]) -> String {
+    let mut payload = data.to_vec();
+    payload.extend_from_slice(&hash256(data)[..4]);
+    encode(&payload)
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    let mut num = Integer::from(0i32);
+
+    for (i, c) in s.bytes().enumerate() {
+        let digit = ALPHABET.iter().position(|&a| a == c).ok_or_else(|| {
+            Base58Error::InvalidCharacter(format!(
+                "{:?} at position {} is not a base58 character",
+                s[i..].chars().next().unwrap(),
+                i
+            ))
+        })?;
+        num = num * 58i32 + digit as i32;
+    }
+
+    let mut data = vec![0u8; zeros];
+    data.extend(num.to_be_bytes());
+    Ok(data)
+}
+
+/// Decodes `s` and checks and strips its four byte checksum.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut data = decode(s)?;
+    if data.len() < 4 {
+        return Err(Base58Error::InvalidLength(format!(
+            "{} decodes to {} bytes, too short for a checksum",
+            s,
+            data.len()
+        )));
+    }
+
+    let checksum = data.split_off(data.len() - 4);
+    if checksum != hash256(&data)[..4] {
+        return Err(Base58Error::InvalidChecksum(format!(
+            "checksum of {} does not match its payload",
+            s
+        )));
+    }
+    Ok(data)
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter(message)
+            | Base58Error::InvalidLength(message)
+            | Base58Error::InvalidChecksum(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Base58Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The encode/decode vectors of Bitcoin Core, src/test/data/base58_encode_decode.json.
+    #[test]
+    fn test_encode_decode_vectors() {
+        let vectors: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/base58_encode_decode.json"
+        )))
+        .unwrap();
+
+        for vector in vectors.as_array().unwrap() {
+            let data = hex::decode(vector[0].as_str().unwrap()).unwrap();
+            let encoded = vector[1].as_str().unwrap();
+
+            assert_eq!(encode(&data), encoded);
+            assert_eq!(decode(encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_characters() {
+        for s in ["0", "O", "I", "l", "3SEo3LWLoPntC ", "3SEo3LW+LoPntC", "é"] {
+            assert!(
+                matches!(decode(s), Err(Base58Error::InvalidCharacter(_))),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        // Address of the compressed public key for the secret 1.
+        let payload = hex::decode("00751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let address = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+
+        assert_eq!(encode_check(&payload), address);
+        assert_eq!(decode_check(address).unwrap(), payload);
+        assert_eq!(decode_check(&encode_check(&[])).unwrap(), Vec::<u8>::new());
+
+        assert!(matches!(
+            decode_check("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"),
+            Err(Base58Error::InvalidChecksum(_))
+        ));
+        assert!(matches!(
+            decode_check("3EF"),
+            Err(Base58Error::InvalidLength(_))
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::{self, Read, Write};
+
+use crate::integer::{BigInteger, Integer};
+
+pub fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn read_u32_le<R: Read>(reader: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(reader)?))
+}
+
+pub fn read_u64_le<R: Read>(reader: &mut R) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(read_array(reader)?))
+}
+
+pub fn write_u32_le<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub fn write_u64_le<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+/// `value` as 32 big-endian bytes, the width of secp256k1 field elements and
+/// scalars.
+///
+/// Panics if `value` is negative or does not fit in 32 bytes.
+pub fn to_be_bytes_32(value: &Integer) -> [u8; 32] {
+    let digits = value.to_be_bytes();
+    assert!(
+        *value >= 0 && digits.len() <= 32,
+        "{} does not fit in 32 bytes",
+        value
+    );
+
+    let mut bytes = [0u8; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_little_endian_integers() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        let mut reader = &bytes[..];
+
+        assert_eq!(read_u32_le(&mut reader).unwrap(), 0x04030201);
+        assert_eq!(read_u32_le(&mut reader).unwrap(), 0x08070605);
+        assert!(read_u32_le(&mut reader).is_err());
+        assert_eq!(read_u64_le(&mut &bytes[..]).unwrap(), 0x0807060504030201);
+
+        let mut buffer = Vec::new();
+        write_u32_le(&mut buffer, 0x04030201).unwrap();
+        write_u64_le(&mut buffer, 0x0c0b0a0908070605).unwrap();
+        assert_eq!(buffer, (1..=12).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_integer_bytes() {
+        let value = Integer::from(0x0102i32);
+        let mut be = [0u8; 32];
+        be[30..].copy_from_slice(&[0x01, 0x02]);
+
+        assert_eq!(to_be_bytes_32(&value), be);
+        assert_eq!(Integer::from_be_bytes(&be), value);
+
+        let max = Integer::from(2i32).pow(256) - 1i32;
+        assert_eq!(to_be_bytes_32(&max), [0xff; 32]);
+        assert_eq!(to_be_bytes_32(&Integer::from(0i32)), [0; 32]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in 32 bytes")]
+    fn test_integer_too_large() {
+        to_be_bytes_32(&Integer::from(2i32).pow(256));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in 32 bytes")]
+    fn test_negative_integer() {
+        to_be_bytes_32(&Integer::from(-1i32));
+    }
+}
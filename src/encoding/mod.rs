@@ -0,0 +1,3 @@
+pub mod base58;
+pub mod fixed_width;
+pub mod varint;
@@ -0,0 +1,154 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use super::fixed_width::{read_array, read_u32_le, read_u64_le};
+
+#[derive(PartialEq, Debug)]
+pub enum VarintError {
+    ReadError(String),
+    NonCanonical(String),
+}
+
+/// Reads a CompactSize integer: values below 0xfd are a single byte, larger
+/// ones follow a 0xfd, 0xfe or 0xff marker as 2, 4 or 8 little-endian bytes.
+/// Values that would fit a shorter form are rejected, as Bitcoin Core does.
+pub fn read_varint<R: Read>(reader: &mut R) -> Result<u64, VarintError> {
+    let [marker] = read_array(reader)?;
+    let (value, min) = match marker {
+        0xfd => (u16::from_le_bytes(read_array(reader)?) as u64, 0xfd),
+        0xfe => (read_u32_le(reader)? as u64, 0x10000),
+        0xff => (read_u64_le(reader)?, 0x1_0000_0000),
+        value => return Ok(value as u64),
+    };
+
+    if value < min {
+        return Err(VarintError::NonCanonical(format!(
+            "{} is not canonically encoded with marker {:#04x}",
+            value, marker
+        )));
+    }
+    Ok(value)
+}
+
+/// Writes `value` in the shortest CompactSize form.
+pub fn write_varint<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    match value {
+        0..=0xfc => writer.write_all(&[value as u8]),
+        0xfd..=0xffff => {
+            writer.write_all(&[0xfd])?;
+            writer.write_all(&(value as u16).to_le_bytes())
+        }
+        0x10000..=0xffffffff => {
+            writer.write_all(&[0xfe])?;
+            writer.write_all(&(value as u32).to_le_bytes())
+        }
+        _ => {
+            writer.write_all(&[0xff])?;
+            writer.write_all(&value.to_le_bytes())
+        }
+    }
+}
+
+/// Reads a varint length followed by that many bytes. The buffer grows as
+/// data arrives, so a bogus length cannot trigger a huge allocation.
+pub fn read_var_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, VarintError> {
+    let len = read_varint(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bytes)
+}
+
+pub fn write_var_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_varint(writer, bytes.len() as u64)?;
+    writer.write_all(bytes)
+}
+
+impl From<io::Error> for VarintError {
+    fn from(error: io::Error) -> VarintError {
+        VarintError::ReadError(format!("Cannot read varint: {}", error))
+    }
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarintError::ReadError(message) | VarintError::NonCanonical(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VarintError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint() {
+        let cases: [(u64, &[u8]); 8] = [
+            (0, &[0x00]),
+            (0xfc, &[0xfc]),
+            (0xfd, &[0xfd, 0xfd, 0x00]),
+            (0xffff, &[0xfd, 0xff, 0xff]),
+            (0x10000, &[0xfe, 0x00, 0x00, 0x01, 0x00]),
+            (0xffffffff, &[0xfe, 0xff, 0xff, 0xff, 0xff]),
+            (0x1_0000_0000, &[0xff, 0, 0, 0, 0, 1, 0, 0, 0]),
+            (u64::MAX, &[0xff; 9]),
+        ];
+
+        for (value, bytes) in cases {
+            let mut buffer = Vec::new();
+            write_varint(&mut buffer, value).unwrap();
+            assert_eq!(buffer, bytes);
+            assert_eq!(read_varint(&mut &bytes[..]).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_read_varint_rejects_invalid_encodings() {
+        let non_canonical: [&[u8]; 4] = [
+            &[0xfd, 0xfc, 0x00],
+            &[0xfe, 0xff, 0xff, 0x00, 0x00],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0],
+            &[0xff, 0, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        for bytes in non_canonical {
+            assert!(matches!(
+                read_varint(&mut &bytes[..]),
+                Err(VarintError::NonCanonical(_))
+            ));
+        }
+
+        let truncated: [&[u8]; 3] = [&[], &[0xfd, 0x01], &[0xff, 0, 0, 0, 0, 1, 0, 0]];
+        for bytes in truncated {
+            assert!(matches!(
+                read_varint(&mut &bytes[..]),
+                Err(VarintError::ReadError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_var_bytes() {
+        let mut buffer = Vec::new();
+        write_var_bytes(&mut buffer, b"abc").unwrap();
+        assert_eq!(buffer, b"\x03abc");
+        assert_eq!(read_var_bytes(&mut &buffer[..]).unwrap(), b"abc");
+
+        assert!(matches!(
+            read_var_bytes(&mut &b"\x04abc"[..]),
+            Err(VarintError::ReadError(_))
+        ));
+        assert!(matches!(
+            read_var_bytes(&mut &[0xff; 9][..]),
+            Err(VarintError::ReadError(_))
+        ));
+    }
+}
@@ -1,21 +1,47 @@
-use rug::Integer;
 use std::fmt;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::Mutex;
+
+use crate::integer::{BigInteger, Integer};
+
+#[cfg(feature = "secp256k1-field")]
+use super::secp256k1_element::Secp256k1Element;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct FieldElement {
-    num: Integer,
-    prime: Integer,
+    repr: Repr,
+}
+
+// With the `secp256k1-field` feature, elements of the secp256k1 base field are
+// kept in fixed-width limbs so their arithmetic never allocates. Any other
+// prime goes through the generic `Integer` representation.
+#[derive(PartialEq, Debug, Clone)]
+enum Repr {
+    Generic {
+        num: Integer,
+        prime: Integer,
+    },
+    #[cfg(feature = "secp256k1-field")]
+    Secp256k1(Secp256k1Element),
 }
 
+// The number of field orders whose primality `check_order` remembers.
+const KNOWN_PRIMES: usize = 64;
+
 #[derive(PartialEq, Debug)]
 pub enum FieldElementError {
     FieldRangeError(String),
     FieldOrderError(String),
+    FieldMismatchError(String),
+    DivisionByZeroError(String),
 }
 
 impl FieldElement {
+    /// Builds the element `num` of the field of order `prime`, checking that
+    /// the order is prime and that `num` is in `0..prime`. The primality test
+    /// only runs the first time an order is seen.
     pub fn new(num: Integer, prime: Integer) -> Result<FieldElement, FieldElementError> {
+        check_order(&prime)?;
         if num >= prime || num < 0 {
             return Err(FieldElementError::FieldRangeError(format!(
                 "Num {} not in field range 0 to {}",
@@ -24,129 +50,401 @@ impl FieldElement {
             )));
         }
 
-        Ok(FieldElement { num, prime })
+        Ok(FieldElement::from_parts(num, prime))
+    }
+
+    /// Same as `new` without any validation, for orders known to be prime
+    /// such as the secp256k1 one and values known to be reduced. With a
+    /// composite order, division and square roots give meaningless results.
+    pub fn new_unchecked(num: Integer, prime: Integer) -> FieldElement {
+        debug_assert!(num >= 0 && num < prime);
+        FieldElement::from_parts(num, prime)
+    }
+
+    fn from_parts(num: Integer, prime: Integer) -> FieldElement {
+        #[cfg(feature = "secp256k1-field")]
+        if Secp256k1Element::is_modulus(&prime) {
+            return FieldElement {
+                repr: Repr::Secp256k1(Secp256k1Element::from_integer(&num)),
+            };
+        }
+
+        FieldElement {
+            repr: Repr::Generic { num, prime },
+        }
     }
 
     pub fn pow(&self, exp: &Integer) -> FieldElement {
-        if self.prime == 1i32 {
-            FieldElement {
-                num: Integer::from(0i32),
-                prime: self.prime.clone(),
+        match &self.repr {
+            Repr::Generic { num, prime } => {
+                if *prime == 1i32 {
+                    FieldElement::from_parts(Integer::from(0i32), prime.clone())
+                } else if let Some(result) = num.modpow(exp, prime) {
+                    FieldElement::from_parts(result, prime.clone())
+                } else {
+                    unreachable!()
+                }
             }
-        } else if let Some(result) = self.num.pow_mod_ref(exp, &self.prime) {
-            FieldElement {
-                num: Integer::from(result),
-                prime: self.prime.clone(),
+            #[cfg(feature = "secp256k1-field")]
+            Repr::Secp256k1(element) => {
+                // Non-zero exponents are taken into 1..=p-1, which also makes
+                // them positive. A multiple of p - 1 must stay non-zero so that
+                // zero to that power is still zero.
+                let order = Secp256k1Element::modulus().clone() - 1i32;
+                let exp = match value_of(exp.clone(), &order) {
+                    reduced if reduced == 0 && *exp != 0 => order,
+                    reduced => reduced,
+                };
+                let mut limbs = [0u64; 4];
+                exp.write_le_limbs(&mut limbs);
+                FieldElement {
+                    repr: Repr::Secp256k1(element.pow(&limbs)),
+                }
             }
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match &self.repr {
+            Repr::Generic { num, .. } => *num == 0,
+            #[cfg(feature = "secp256k1-field")]
+            Repr::Secp256k1(element) => element.is_zero(),
+        }
+    }
+
+    /// Returns the Legendre symbol of this element: 0 for zero, 1 for a
+    /// non-zero square and -1 for a non-square, computed with Euler's criterion.
+    pub fn legendre(&self) -> i32 {
+        if self.is_zero() {
+            return 0;
+        }
+
+        let euler = self.pow(&((self.prime().clone() - 1i32) >> 1));
+        if euler == self.constant(1) {
+            1
         } else {
-            unreachable!()
+            -1
         }
     }
 
-    pub fn prime(self) -> Integer {
-        self.prime
+    pub fn is_square(&self) -> bool {
+        self.legendre() >= 0
     }
 
-    pub fn num(self) -> Integer {
-        self.num
+    /// Returns a square root of this element, if one exists. Uses
+    /// `num^((p + 1) / 4)` when p is congruent to 3 mod 4 (as is the case for
+    /// secp256k1) and falls back to Tonelli-Shanks otherwise.
+    pub fn sqrt(&self) -> Option<FieldElement> {
+        let prime = self.prime().clone();
+        if self.is_zero() || prime == 2i32 {
+            return Some(self.clone());
+        }
+        if self.legendre() != 1 {
+            return None;
+        }
+        if prime.rem_u32(4) == 3 {
+            return Some(self.pow(&((prime + 1i32) >> 2)));
+        }
+
+        Some(self.tonelli_shanks())
     }
 
-    fn value_of(&self, value: Integer) -> Integer {
-        if value < 0 || value >= self.prime {
-            let result = value.div_rem_euc_ref(&self.prime);
-            let (_, normalized_value) = <(Integer, Integer)>::from(result);
-            normalized_value
-        } else {
-            value
+    // Expects `self` to be a non-zero square in a field of odd order.
+    fn tonelli_shanks(&self) -> FieldElement {
+        let p_minus_one = self.prime().clone() - 1i32;
+        let mut m = p_minus_one.trailing_zeros().unwrap();
+        let q = p_minus_one >> m;
+
+        let one = self.constant(1);
+        let mut non_residue = &one + &one;
+        while non_residue.legendre() != -1 {
+            non_residue += &one;
+        }
+
+        let mut c = non_residue.pow(&q);
+        let mut t = self.pow(&q);
+        let mut root = self.pow(&((q.clone() + 1i32) >> 1));
+
+        while t != one {
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != one {
+                t_pow = &t_pow * &t_pow;
+                i += 1;
+            }
+
+            let b = c.pow(&(Integer::from(1i32) << (m - i - 1)));
+            m = i;
+            c = &b * &b;
+            t *= &c;
+            root *= b;
         }
+        root
     }
-}
 
-impl fmt::Display for FieldElement {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "FieldElement_{}({})", self.prime, self.num)
+    pub fn prime(&self) -> &Integer {
+        match &self.repr {
+            Repr::Generic { prime, .. } => prime,
+            #[cfg(feature = "secp256k1-field")]
+            Repr::Secp256k1(_) => Secp256k1Element::modulus(),
+        }
     }
-}
 
-impl Add for FieldElement {
-    type Output = FieldElement;
+    pub fn num(&self) -> Integer {
+        match &self.repr {
+            Repr::Generic { num, .. } => num.clone(),
+            #[cfg(feature = "secp256k1-field")]
+            Repr::Secp256k1(element) => element.to_integer(),
+        }
+    }
 
-    fn add(self, rhs: FieldElement) -> Self::Output {
-        assert_eq!(
-            self.prime, rhs.prime,
-            "Cannot add two numbers in different fields"
-        );
-        let sum = &self.num + rhs.num;
-        if sum < 0 || sum >= self.prime {
-            FieldElement {
-                num: self.value_of(sum),
-                prime: self.prime,
+    pub fn checked_add(&self, rhs: &FieldElement) -> Result<FieldElement, FieldElementError> {
+        match (&self.repr, &rhs.repr) {
+            (
+                Repr::Generic { num, prime },
+                Repr::Generic {
+                    num: rhs_num,
+                    prime: rhs_prime,
+                },
+            ) if prime == rhs_prime => {
+                let sum = num.clone() + rhs_num;
+                Ok(FieldElement::from_parts(
+                    value_of(sum, prime),
+                    prime.clone(),
+                ))
             }
-        } else {
-            FieldElement {
-                num: sum,
-                prime: self.prime,
+            #[cfg(feature = "secp256k1-field")]
+            (Repr::Secp256k1(a), Repr::Secp256k1(b)) => Ok(FieldElement {
+                repr: Repr::Secp256k1(a.add(b)),
+            }),
+            _ => Err(self.field_mismatch(rhs, "add")),
+        }
+    }
+
+    pub fn checked_sub(&self, rhs: &FieldElement) -> Result<FieldElement, FieldElementError> {
+        match (&self.repr, &rhs.repr) {
+            (
+                Repr::Generic { num, prime },
+                Repr::Generic {
+                    num: rhs_num,
+                    prime: rhs_prime,
+                },
+            ) if prime == rhs_prime => {
+                let difference = num.clone() - rhs_num;
+                Ok(FieldElement::from_parts(
+                    value_of(difference, prime),
+                    prime.clone(),
+                ))
             }
+            #[cfg(feature = "secp256k1-field")]
+            (Repr::Secp256k1(a), Repr::Secp256k1(b)) => Ok(FieldElement {
+                repr: Repr::Secp256k1(a.sub(b)),
+            }),
+            _ => Err(self.field_mismatch(rhs, "subtract")),
         }
     }
-    // TODO: implement add() with references, to remove clones.
-}
 
-impl Sub for FieldElement {
-    type Output = FieldElement;
+    pub fn checked_mul(&self, rhs: &FieldElement) -> Result<FieldElement, FieldElementError> {
+        match (&self.repr, &rhs.repr) {
+            (
+                Repr::Generic { num, prime },
+                Repr::Generic {
+                    num: rhs_num,
+                    prime: rhs_prime,
+                },
+            ) if prime == rhs_prime => {
+                let product = num.clone() * rhs_num;
+                Ok(FieldElement::from_parts(
+                    value_of(product, prime),
+                    prime.clone(),
+                ))
+            }
+            #[cfg(feature = "secp256k1-field")]
+            (Repr::Secp256k1(a), Repr::Secp256k1(b)) => Ok(FieldElement {
+                repr: Repr::Secp256k1(a.mul(b)),
+            }),
+            _ => Err(self.field_mismatch(rhs, "multiply")),
+        }
+    }
 
-    fn sub(self, rhs: FieldElement) -> Self::Output {
-        assert_eq!(
-            self.prime, rhs.prime,
-            "Cannot substract two numbers in different fields"
-        );
+    pub fn checked_div(&self, rhs: &FieldElement) -> Result<FieldElement, FieldElementError> {
+        if rhs.is_zero() {
+            return Err(FieldElementError::DivisionByZeroError(format!(
+                "Cannot divide {} by zero",
+                self
+            )));
+        }
 
-        FieldElement {
-            num: self.value_of(&self.num - rhs.num),
-            prime: self.prime,
+        match (&self.repr, &rhs.repr) {
+            (
+                Repr::Generic { prime, .. },
+                Repr::Generic {
+                    prime: rhs_prime, ..
+                },
+            ) if prime == rhs_prime => {
+                let exp = prime.clone() - 2i32;
+                self.checked_mul(&rhs.pow(&exp))
+            }
+            #[cfg(feature = "secp256k1-field")]
+            (Repr::Secp256k1(a), Repr::Secp256k1(b)) => Ok(FieldElement {
+                repr: Repr::Secp256k1(a.mul(&b.invert())),
+            }),
+            _ => Err(self.field_mismatch(rhs, "divide")),
         }
     }
-}
 
-impl Neg for FieldElement {
-    type Output = FieldElement;
+    fn field_mismatch(&self, rhs: &FieldElement, operation: &str) -> FieldElementError {
+        FieldElementError::FieldMismatchError(format!(
+            "Cannot {} numbers in different fields {} and {}",
+            operation,
+            self.prime(),
+            rhs.prime()
+        ))
+    }
+
+    /// Returns `a` when `choice` is false and `b` when it is true, without
+    /// branching on `choice`. On the generic representation this is arithmetic
+    /// on variable-length integers, whose timing depends on the values; only
+    /// the fixed-width secp256k1 representation selects with a plain mask.
+    pub(crate) fn select(a: &FieldElement, b: &FieldElement, choice: bool) -> FieldElement {
+        match (&a.repr, &b.repr) {
+            (Repr::Generic { num, prime }, Repr::Generic { num: b_num, .. }) => {
+                let num = num.clone() + (b_num.clone() - num) * (choice as i32);
+                FieldElement::from_parts(num, prime.clone())
+            }
+            #[cfg(feature = "secp256k1-field")]
+            (Repr::Secp256k1(a), Repr::Secp256k1(b)) => FieldElement {
+                repr: Repr::Secp256k1(Secp256k1Element::select(a, b, choice as u64)),
+            },
+            #[cfg(feature = "secp256k1-field")]
+            _ => panic!("Cannot select between numbers in different fields"),
+        }
+    }
 
-    fn neg(self) -> Self::Output {
-        let zero = FieldElement {
-            num: Integer::from(0i32),
-            prime: self.prime.clone(),
-        };
-        zero - self
+    // Element of the same field as `self` holding `value` reduced mod p.
+    fn constant(&self, value: i32) -> FieldElement {
+        let prime = self.prime();
+        FieldElement::from_parts(value_of(Integer::from(value), prime), prime.clone())
     }
 }
 
-impl Mul for FieldElement {
-    type Output = FieldElement;
+fn value_of(value: Integer, prime: &Integer) -> Integer {
+    if value < 0 || value >= *prime {
+        value.rem_euclid(prime)
+    } else {
+        value
+    }
+}
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        assert_eq!(
-            self.prime, rhs.prime,
-            "Cannot multiply two numbers in different fields"
-        );
+// Fails unless `prime` is a probable prime. Orders that passed are
+// remembered, so building many elements of one field tests it only once.
+fn check_order(prime: &Integer) -> Result<(), FieldElementError> {
+    static PRIMES: Mutex<Vec<Integer>> = Mutex::new(Vec::new());
 
-        FieldElement {
-            num: self.value_of(self.num.clone() * rhs.num),
-            prime: self.prime,
+    let mut primes = PRIMES.lock().unwrap();
+    if primes.contains(prime) {
+        return Ok(());
+    }
+    if !prime.is_probable_prime() {
+        return Err(FieldElementError::FieldOrderError(format!(
+            "Order {} is not prime",
+            prime
+        )));
+    }
+    if primes.len() < KNOWN_PRIMES {
+        primes.push(prime.clone());
+    }
+    Ok(())
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FieldElement_{}({})", self.prime(), self.num())
+    }
+}
+
+impl fmt::Display for FieldElementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldElementError::FieldRangeError(message)
+            | FieldElementError::FieldOrderError(message)
+            | FieldElementError::FieldMismatchError(message)
+            | FieldElementError::DivisionByZeroError(message) => write!(f, "{}", message),
         }
     }
 }
 
-impl Div for FieldElement {
+impl std::error::Error for FieldElementError {}
+
+// The operators panic where the `checked_*` methods return an error. Owned and
+// mixed operands, and the assigning operators, forward to the implementation
+// on references.
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $checked:ident, $assign:ident, $assign_method:ident) => {
+        impl $op<&FieldElement> for &FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, rhs: &FieldElement) -> FieldElement {
+                self.$checked(rhs).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+
+        impl $op for FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, rhs: FieldElement) -> FieldElement {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $op<&FieldElement> for FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, rhs: &FieldElement) -> FieldElement {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $op<FieldElement> for &FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, rhs: FieldElement) -> FieldElement {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $assign for FieldElement {
+            fn $assign_method(&mut self, rhs: FieldElement) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $assign<&FieldElement> for FieldElement {
+            fn $assign_method(&mut self, rhs: &FieldElement) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    };
+}
+
+binary_op!(Add, add, checked_add, AddAssign, add_assign);
+binary_op!(Sub, sub, checked_sub, SubAssign, sub_assign);
+binary_op!(Mul, mul, checked_mul, MulAssign, mul_assign);
+binary_op!(Div, div, checked_div, DivAssign, div_assign);
+
+impl Neg for &FieldElement {
     type Output = FieldElement;
 
-    fn div(self, rhs: Self) -> Self::Output {
-        assert_eq!(
-            self.prime, rhs.prime,
-            "Cannot divide two numbers in different fields"
-        );
+    fn neg(self) -> FieldElement {
+        &self.constant(0) - self
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = FieldElement;
 
-        assert_ne!(Integer::from(0i32), rhs.num, "Zero is not a valid divisor!");
-        self.clone() * rhs.pow(&(self.prime - Integer::from(2i32)))
+    fn neg(self) -> FieldElement {
+        -&self
     }
 }
 
@@ -154,6 +452,39 @@ impl Div for FieldElement {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_new() {
+        assert!(FieldElement::new(Integer::from(3i32), Integer::from(31i32)).is_ok());
+        assert!(matches!(
+            FieldElement::new(Integer::from(31i32), Integer::from(31i32)),
+            Err(FieldElementError::FieldRangeError(_))
+        ));
+        assert!(matches!(
+            FieldElement::new(Integer::from(-1i32), Integer::from(31i32)),
+            Err(FieldElementError::FieldRangeError(_))
+        ));
+
+        assert_eq!(
+            FieldElement::new(Integer::from(3i32), Integer::from(57i32)),
+            Err(FieldElementError::FieldOrderError(String::from(
+                "Order 57 is not prime"
+            )))
+        );
+        assert!(matches!(
+            FieldElement::new(Integer::from(0i32), Integer::from(1i32)),
+            Err(FieldElementError::FieldOrderError(_))
+        ));
+        // A prime order that was already checked is accepted from the cache.
+        assert!(FieldElement::new(Integer::from(4i32), Integer::from(31i32)).is_ok());
+
+        let a = FieldElement::new_unchecked(Integer::from(44i32), Integer::from(57i32));
+        let b = FieldElement::new_unchecked(Integer::from(33i32), Integer::from(57i32));
+        assert_eq!(
+            a + b,
+            FieldElement::new_unchecked(Integer::from(20i32), Integer::from(57i32))
+        );
+    }
+
     #[test]
     fn test_ne() {
         let a = FieldElement::new(Integer::from(2i32), Integer::from(31i32)).unwrap();
@@ -265,4 +596,172 @@ mod tests {
             FieldElement::new(Integer::from(13i32), Integer::from(31i32)).unwrap()
         );
     }
+
+    #[test]
+    fn test_reference_operators() {
+        let a = FieldElement::new(Integer::from(17i32), Integer::from(31i32)).unwrap();
+        let b = FieldElement::new(Integer::from(21i32), Integer::from(31i32)).unwrap();
+
+        assert_eq!(&a + &b, a.clone() + b.clone());
+        assert_eq!(&a - &b, a.clone() - &b);
+        assert_eq!(&a * &b, &a * b.clone());
+        assert_eq!(&a / &b, a.clone() / b.clone());
+        assert_eq!(-&a, -a.clone());
+
+        let mut c = a.clone();
+        c += &b;
+        c -= b.clone();
+        c *= &b;
+        c /= &b;
+        assert_eq!(c, a);
+        assert_eq!(a.num(), 17i32);
+        assert_eq!(a.prime(), &Integer::from(31i32));
+    }
+
+    #[test]
+    fn test_checked_operations() {
+        let a = FieldElement::new(Integer::from(3i32), Integer::from(31i32)).unwrap();
+        let b = FieldElement::new(Integer::from(24i32), Integer::from(31i32)).unwrap();
+        let c = FieldElement::new(Integer::from(3i32), Integer::from(19i32)).unwrap();
+        let zero = FieldElement::new(Integer::from(0i32), Integer::from(31i32)).unwrap();
+
+        assert_eq!(a.checked_add(&b), Ok(&a + &b));
+        assert_eq!(a.checked_sub(&b), Ok(&a - &b));
+        assert_eq!(a.checked_mul(&b), Ok(&a * &b));
+        assert_eq!(a.checked_div(&b), Ok(&a / &b));
+
+        assert!(matches!(
+            a.checked_add(&c),
+            Err(FieldElementError::FieldMismatchError(_))
+        ));
+        assert!(matches!(
+            a.checked_sub(&c),
+            Err(FieldElementError::FieldMismatchError(_))
+        ));
+        assert!(matches!(
+            a.checked_mul(&c),
+            Err(FieldElementError::FieldMismatchError(_))
+        ));
+        assert!(matches!(
+            a.checked_div(&c),
+            Err(FieldElementError::FieldMismatchError(_))
+        ));
+        assert!(matches!(
+            a.checked_div(&zero),
+            Err(FieldElementError::DivisionByZeroError(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot add numbers in different fields")]
+    fn test_add_different_fields() {
+        let a = FieldElement::new(Integer::from(3i32), Integer::from(31i32)).unwrap();
+        let b = FieldElement::new(Integer::from(3i32), Integer::from(19i32)).unwrap();
+        let _ = a + b;
+    }
+
+    #[test]
+    fn test_legendre() {
+        let prime = Integer::from(223i32);
+        let squares: Vec<Integer> = (1..223).map(|num| Integer::from(num * num % 223)).collect();
+
+        for num in 0..223 {
+            let a = FieldElement::new(Integer::from(num), prime.clone()).unwrap();
+            let expected = if num == 0 {
+                0
+            } else if squares.contains(&Integer::from(num)) {
+                1
+            } else {
+                -1
+            };
+
+            assert_eq!(a.legendre(), expected);
+            assert_eq!(a.is_square(), expected >= 0);
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let prime = Integer::from(223i32);
+        for num in 0..223 {
+            let a = FieldElement::new(Integer::from(num), prime.clone()).unwrap();
+            let square = a.clone() * a.clone();
+            let root = square.sqrt().unwrap();
+
+            assert!(root == a || root == -a);
+        }
+
+        let a = FieldElement::new(Integer::from(5i32), prime).unwrap();
+        assert_eq!(a.sqrt(), None);
+    }
+
+    #[test]
+    fn test_sqrt_tonelli_shanks() {
+        // 97 - 1 = 3 * 2^5 and 257 - 1 = 2^8 exercise the general algorithm.
+        for prime in [2i32, 5, 13, 17, 97, 257] {
+            for num in 0..prime {
+                let a = FieldElement::new(Integer::from(num), Integer::from(prime)).unwrap();
+                match a.sqrt() {
+                    Some(root) => {
+                        assert!(a.is_square());
+                        assert_eq!(root.clone() * root, a);
+                    }
+                    None => assert!(!a.is_square()),
+                }
+            }
+        }
+
+        let prime = Integer::from(2i32).pow(255) - Integer::from(19i32);
+        let a = FieldElement::new(Integer::from(123456789i32), prime).unwrap();
+        let square = a.clone() * a.clone();
+        let root = square.sqrt().unwrap();
+        assert!(root == a || root == -a);
+    }
+
+    #[test]
+    fn test_sqrt_secp256k1() {
+        let prime =
+            Integer::from(2i32).pow(256) - Integer::from(2i32).pow(32) - Integer::from(977i32);
+        let gx = Integer::from_radix_str(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            16,
+        )
+        .unwrap();
+        let gy = Integer::from_radix_str(
+            "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            16,
+        )
+        .unwrap();
+
+        let x = FieldElement::new(gx, prime.clone()).unwrap();
+        let y = FieldElement::new(gy, prime.clone()).unwrap();
+        let seven = FieldElement::new(Integer::from(7i32), prime.clone()).unwrap();
+
+        let alpha = x.pow(&Integer::from(3i32)) + seven;
+        assert_eq!(alpha.legendre(), 1);
+        let beta = alpha.sqrt().unwrap();
+        assert!(beta == y || beta == -y);
+
+        // 132 = 5^3 + 7: x = 5 is not the x coordinate of any secp256k1 point.
+        let alpha = FieldElement::new(Integer::from(132i32), prime).unwrap();
+        assert_eq!(alpha.legendre(), -1);
+        assert_eq!(alpha.sqrt(), None);
+    }
+
+    #[test]
+    fn test_pow_zero_secp256k1() {
+        let prime =
+            Integer::from(2i32).pow(256) - Integer::from(2i32).pow(32) - Integer::from(977i32);
+        let order = prime.clone() - 1i32;
+        let zero = FieldElement::new(Integer::from(0i32), prime.clone()).unwrap();
+        let one = FieldElement::new(Integer::from(1i32), prime.clone()).unwrap();
+        let two = FieldElement::new(Integer::from(2i32), prime).unwrap();
+
+        // Exponents that are multiples of p - 1 still give zero for zero.
+        assert_eq!(zero.pow(&order), zero);
+        assert_eq!(zero.pow(&(order.clone() * 3i32)), zero);
+        assert_eq!(zero.pow(&Integer::from(0i32)), one);
+        assert_eq!(two.pow(&order), one);
+        assert_eq!(two.pow(&(order * 2i32)), one);
+    }
 }
@@ -1 +1,3 @@
 pub mod field_element;
+#[cfg(feature = "secp256k1-field")]
+pub mod secp256k1_element;
@@ -0,0 +1,260 @@
+use std::sync::OnceLock;
+
+use crate::integer::{BigInteger, Integer};
+
+// p = 2^256 - 2^32 - 977, as little-endian 64-bit limbs.
+const P: [u64; 4] = [
+    0xffff_fffe_ffff_fc2f,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+
+// p - 2, the exponent used for inversion.
+const P_MINUS_2: [u64; 4] = [
+    0xffff_fffe_ffff_fc2d,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+
+// 2^256 mod p = 2^32 + 977, which lets any multiple of 2^256 be folded back
+// into the low limbs with a single small multiplication.
+const C: u64 = 0x1_0000_03d1;
+
+/// Element of the secp256k1 base field stored as four little-endian 64-bit
+/// limbs, always fully reduced below p. Arithmetic never allocates and has no
+/// data-dependent branches.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Secp256k1Element([u64; 4]);
+
+impl Secp256k1Element {
+    pub const ZERO: Secp256k1Element = Secp256k1Element([0, 0, 0, 0]);
+    pub const ONE: Secp256k1Element = Secp256k1Element([1, 0, 0, 0]);
+
+    pub fn is_modulus(prime: &Integer) -> bool {
+        if prime.bits() != 256 {
+            return false;
+        }
+        let mut limbs = [0u64; 4];
+        prime.write_le_limbs(&mut limbs);
+        limbs == P
+    }
+
+    /// Expects `0 <= value < p`.
+    pub fn from_integer(value: &Integer) -> Secp256k1Element {
+        debug_assert!(*value >= 0 && value.bits() <= 256);
+        let mut limbs = [0u64; 4];
+        value.write_le_limbs(&mut limbs);
+        Secp256k1Element(limbs)
+    }
+
+    pub fn to_integer(&self) -> Integer {
+        Integer::from_le_limbs(&self.0)
+    }
+
+    pub fn modulus() -> &'static Integer {
+        static MODULUS: OnceLock<Integer> = OnceLock::new();
+        MODULUS.get_or_init(|| Integer::from_le_limbs(&P))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0, 0, 0, 0]
+    }
+
+    pub fn add(&self, rhs: &Secp256k1Element) -> Secp256k1Element {
+        let mut sum = [0u64; 4];
+        let mut carry = 0;
+        for (i, limb) in sum.iter_mut().enumerate() {
+            (*limb, carry) = adc(self.0[i], rhs.0[i], carry);
+        }
+        Secp256k1Element(reduce_wide(sum, carry))
+    }
+
+    pub fn sub(&self, rhs: &Secp256k1Element) -> Secp256k1Element {
+        let mut difference = [0u64; 4];
+        let mut borrow = 0;
+        for (i, limb) in difference.iter_mut().enumerate() {
+            (*limb, borrow) = sbb(self.0[i], rhs.0[i], borrow);
+        }
+
+        // On underflow add p back, which wraps around 2^256.
+        let mask = borrow.wrapping_neg();
+        let mut carry = 0;
+        for (i, limb) in difference.iter_mut().enumerate() {
+            (*limb, carry) = adc(*limb, P[i] & mask, carry);
+        }
+        Secp256k1Element(difference)
+    }
+
+    pub fn neg(&self) -> Secp256k1Element {
+        Secp256k1Element::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, rhs: &Secp256k1Element) -> Secp256k1Element {
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u64;
+            for j in 0..4 {
+                let t = wide[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry as u128;
+                wide[i + j] = t as u64;
+                carry = (t >> 64) as u64;
+            }
+            wide[i + 4] = carry;
+        }
+
+        // lo + hi * 2^256 = lo + hi * C (mod p)
+        let mut folded = [0u64; 4];
+        let mut carry = 0u64;
+        for (i, limb) in folded.iter_mut().enumerate() {
+            let t = wide[i] as u128 + wide[i + 4] as u128 * C as u128 + carry as u128;
+            *limb = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        Secp256k1Element(reduce_wide(folded, carry))
+    }
+
+    pub fn square(&self) -> Secp256k1Element {
+        self.mul(self)
+    }
+
+    /// Raises the element to the 256-bit exponent given as little-endian
+    /// limbs, always performing 256 squarings and 256 multiplications.
+    pub fn pow(&self, exp: &[u64; 4]) -> Secp256k1Element {
+        let mut result = Secp256k1Element::ONE;
+        for bit in (0..256).rev() {
+            result = result.square();
+            let product = result.mul(self);
+            let set = (exp[bit / 64] >> (bit % 64)) & 1;
+            result = Secp256k1Element::select(&result, &product, set);
+        }
+        result
+    }
+
+    /// Multiplicative inverse through Fermat's little theorem. The inverse of
+    /// zero is zero.
+    pub fn invert(&self) -> Secp256k1Element {
+        self.pow(&P_MINUS_2)
+    }
+
+    /// Returns `a` when `choice` is 0 and `b` when it is 1, without branching.
+    pub fn select(a: &Secp256k1Element, b: &Secp256k1Element, choice: u64) -> Secp256k1Element {
+        let mask = choice.wrapping_neg();
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            *limb = (a.0[i] & !mask) | (b.0[i] & mask);
+        }
+        Secp256k1Element(limbs)
+    }
+}
+
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+// Reduces `top * 2^256 + limbs` below p, for `top` well under 2^64 / C.
+fn reduce_wide(limbs: [u64; 4], top: u64) -> [u64; 4] {
+    let folded = top as u128 * C as u128;
+    let mut reduced = [0u64; 4];
+    let mut carry;
+    (reduced[0], carry) = adc(limbs[0], folded as u64, 0);
+    (reduced[1], carry) = adc(limbs[1], (folded >> 64) as u64, carry);
+    (reduced[2], carry) = adc(limbs[2], 0, carry);
+    (reduced[3], carry) = adc(limbs[3], 0, carry);
+
+    // A second overflow leaves only a small value in the limbs, so folding it
+    // once more cannot carry out again.
+    (reduced[0], carry) = adc(reduced[0], carry * C, 0);
+    (reduced[1], carry) = adc(reduced[1], 0, carry);
+    (reduced[2], carry) = adc(reduced[2], 0, carry);
+    (reduced[3], _) = adc(reduced[3], 0, carry);
+
+    subtract_p_if_needed(reduced)
+}
+
+fn subtract_p_if_needed(limbs: [u64; 4]) -> [u64; 4] {
+    let mut difference = [0u64; 4];
+    let mut borrow = 0;
+    for (i, limb) in difference.iter_mut().enumerate() {
+        (*limb, borrow) = sbb(limbs[i], P[i], borrow);
+    }
+
+    // Keep the original limbs when subtracting p underflowed.
+    let mask = borrow.wrapping_neg();
+    let mut result = [0u64; 4];
+    for (i, limb) in result.iter_mut().enumerate() {
+        *limb = (limbs[i] & mask) | (difference[i] & !mask);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_modulus() {
+        let p = Secp256k1Element::modulus().clone();
+        assert!(Secp256k1Element::is_modulus(&p));
+        assert!(!Secp256k1Element::is_modulus(&Integer::from(223i32)));
+        assert!(!Secp256k1Element::is_modulus(&(p.clone() - 2i32)));
+        assert_eq!(
+            Secp256k1Element::from_integer(&(p - 1i32)).add(&Secp256k1Element::ONE),
+            Secp256k1Element::ZERO
+        );
+    }
+
+    #[test]
+    fn test_matches_integer_arithmetic() {
+        let p = Secp256k1Element::modulus().clone();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut values = vec![
+            Integer::from(0i32),
+            Integer::from(1i32),
+            p.clone() - 1i32,
+            p.clone() - 2i32,
+            Integer::from(u64::MAX),
+        ];
+        for _ in 0..20 {
+            values.push(p.sample_below(&mut rng));
+        }
+
+        for a in values.iter() {
+            let fa = Secp256k1Element::from_integer(a);
+            assert_eq!(&fa.to_integer(), a);
+            assert_eq!(fa.neg().to_integer(), (-a.clone()).rem_euclid(&p));
+
+            for b in values.iter() {
+                let fb = Secp256k1Element::from_integer(b);
+                assert_eq!(fa.add(&fb).to_integer(), (a.clone() + b).rem_euclid(&p));
+                assert_eq!(fa.sub(&fb).to_integer(), (a.clone() - b).rem_euclid(&p));
+                assert_eq!(fa.mul(&fb).to_integer(), (a.clone() * b).rem_euclid(&p));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        let p = Secp256k1Element::modulus().clone();
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..10 {
+            let a = p.sample_below(&mut rng);
+            let fa = Secp256k1Element::from_integer(&a);
+            let expected = a.mod_inverse(&p).unwrap_or_default();
+
+            assert_eq!(fa.invert().to_integer(), expected);
+            if !fa.is_zero() {
+                assert_eq!(fa.mul(&fa.invert()), Secp256k1Element::ONE);
+            }
+        }
+        assert_eq!(Secp256k1Element::ZERO.invert(), Secp256k1Element::ZERO);
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::{Debug, Display};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Shl, ShlAssign, Shr, ShrAssign, Sub,
+    SubAssign,
+};
+
+use rand::RngCore;
+
+#[cfg(not(any(feature = "rug", feature = "pure-rust")))]
+compile_error!("enable either the `rug` or the `pure-rust` feature");
+
+/// The arbitrary precision integer used throughout the crate: `rug::Integer`
+/// (GMP) by default, or a pure-Rust implementation with the `pure-rust`
+/// feature.
+#[cfg(all(feature = "rug", not(feature = "pure-rust")))]
+pub use rug::Integer;
+
+#[cfg(feature = "pure-rust")]
+pub use super::pure_integer::PureInteger as Integer;
+
+/// Integer operations needed by the field, curve and signature code.
+///
+/// Besides the methods below, implementors support the arithmetic operators
+/// with an owned left-hand side, which is the subset shared by every backend.
+/// Division and remainder truncate towards zero, and right shifts round
+/// towards negative infinity.
+pub trait BigInteger:
+    Sized
+    + Clone
+    + Default
+    + Eq
+    + Ord
+    + Debug
+    + Display
+    + From<i32>
+    + From<u32>
+    + From<i64>
+    + From<u64>
+    + for<'a> From<&'a Self>
+    + PartialEq<i32>
+    + PartialOrd<i32>
+    + Add<Output = Self>
+    + for<'a> Add<&'a Self, Output = Self>
+    + Add<i32, Output = Self>
+    + Sub<Output = Self>
+    + for<'a> Sub<&'a Self, Output = Self>
+    + Sub<i32, Output = Self>
+    + Mul<Output = Self>
+    + for<'a> Mul<&'a Self, Output = Self>
+    + Mul<i32, Output = Self>
+    + Div<Output = Self>
+    + for<'a> Div<&'a Self, Output = Self>
+    + Div<i32, Output = Self>
+    + Rem<Output = Self>
+    + for<'a> Rem<&'a Self, Output = Self>
+    + AddAssign
+    + for<'a> AddAssign<&'a Self>
+    + AddAssign<i32>
+    + SubAssign
+    + for<'a> SubAssign<&'a Self>
+    + SubAssign<i32>
+    + MulAssign
+    + for<'a> MulAssign<&'a Self>
+    + MulAssign<i32>
+    + DivAssign<i32>
+    + Neg<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+    + ShlAssign<u32>
+    + ShrAssign<u32>
+{
+    /// Parses an optionally signed number written in `radix` (2 to 36).
+    fn from_radix_str(src: &str, radix: u32) -> Option<Self>;
+
+    /// Non-negative integer from its big-endian bytes.
+    fn from_be_bytes(bytes: &[u8]) -> Self;
+
+    /// Minimal big-endian bytes of the absolute value, empty for zero.
+    fn to_be_bytes(&self) -> Vec<u8>;
+
+    /// Non-negative integer from little-endian 64-bit limbs.
+    fn from_le_limbs(limbs: &[u64]) -> Self;
+
+    /// Writes the absolute value as little-endian 64-bit limbs, dropping any
+    /// that do not fit in `limbs`.
+    fn write_le_limbs(&self, limbs: &mut [u64]);
+
+    fn pow(self, exp: u32) -> Self;
+
+    /// `self^exp mod modulus` in `0..modulus`. A negative exponent uses the
+    /// modular inverse, so `None` is returned when there is none.
+    fn modpow(&self, exp: &Self, modulus: &Self) -> Option<Self>;
+
+    /// The inverse of `self` modulo `modulus`, if they are coprime.
+    fn mod_inverse(&self, modulus: &Self) -> Option<Self>;
+
+    /// The remainder of `self / modulus` in `0..|modulus|`.
+    fn rem_euclid(&self, modulus: &Self) -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    fn is_odd(&self) -> bool;
+
+    fn is_even(&self) -> bool {
+        !self.is_odd()
+    }
+
+    fn is_multiple_of(&self, divisor: &Self) -> bool;
+
+    /// `self mod modulus` for a non-negative `self`.
+    fn rem_u32(&self, modulus: u32) -> u32;
+
+    /// Number of bits of the absolute value, 0 for zero.
+    fn bits(&self) -> u32;
+
+    /// Bit `index` of a non-negative integer.
+    fn bit(&self, index: u32) -> bool;
+
+    /// Index of the lowest set bit, `None` for zero.
+    fn trailing_zeros(&self) -> Option<u32>;
+
+    /// Baillie-PSW probable prime test, which has no known counterexamples.
+    fn is_probable_prime(&self) -> bool {
+        super::primality::is_probable_prime(self)
+    }
+
+    /// Uniformly random integer in `0..self`, for a positive `self`.
+    fn sample_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
+        assert!(*self > 0, "Upper bound must be positive");
+        let bits = self.bits();
+        let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+        let excess = bytes.len() as u32 * 8 - bits;
+        loop {
+            rng.fill_bytes(&mut bytes);
+            bytes[0] &= 0xff >> excess;
+            let candidate = Self::from_be_bytes(&bytes);
+            if candidate < *self {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_radix_and_bytes() {
+        let value = Integer::from_radix_str("-1f", 16).unwrap();
+        assert_eq!(value, -31i32);
+        assert_eq!(Integer::from_radix_str("12x", 10), None);
+
+        let value = Integer::from_radix_str("0102030405060708090a", 16).unwrap();
+        assert_eq!(value.to_be_bytes(), (1u8..=10).collect::<Vec<u8>>());
+        assert_eq!(Integer::from_be_bytes(&value.to_be_bytes()), value);
+        assert_eq!(Integer::from(0i32).to_be_bytes(), Vec::<u8>::new());
+        assert_eq!(Integer::from_be_bytes(&[]), 0i32);
+
+        let mut limbs = [0u64; 3];
+        value.write_le_limbs(&mut limbs);
+        assert_eq!(limbs, [0x0304_0506_0708_090a, 0x0102, 0]);
+        assert_eq!(Integer::from_le_limbs(&limbs), value);
+    }
+
+    #[test]
+    fn test_division_rounding() {
+        let a = Integer::from(-7i32);
+        let b = Integer::from(2i32);
+
+        assert_eq!(a.clone() / &b, -3i32);
+        assert_eq!(a.clone() % &b, -1i32);
+        assert_eq!(a.rem_euclid(&b), 1i32);
+        assert_eq!(a.clone() >> 1, -4i32);
+        assert_eq!(a << 2, -28i32);
+    }
+
+    #[test]
+    fn test_modular_arithmetic() {
+        let p = Integer::from(223i32);
+        let a = Integer::from(17i32);
+
+        assert_eq!(a.modpow(&Integer::from(222i32), &p).unwrap(), 1i32);
+        let inverse = a.mod_inverse(&p).unwrap();
+        assert_eq!((inverse.clone() * &a).rem_euclid(&p), 1i32);
+        assert_eq!(a.modpow(&Integer::from(-1i32), &p).unwrap(), inverse);
+        assert_eq!(Integer::from(6i32).mod_inverse(&Integer::from(9i32)), None);
+        assert_eq!(
+            Integer::from(-5i32)
+                .modpow(&Integer::from(3i32), &p)
+                .unwrap(),
+            98i32
+        );
+    }
+
+    #[test]
+    fn test_bits() {
+        let value = Integer::from(0b1011000i32);
+
+        assert_eq!(value.bits(), 7);
+        assert_eq!(value.trailing_zeros(), Some(3));
+        assert!(value.bit(3) && value.bit(6) && !value.bit(5));
+        assert!(value.is_even() && !value.is_odd());
+        assert_eq!(value.rem_u32(10), 8);
+        assert!(value.is_multiple_of(&Integer::from(11i32)));
+        assert_eq!(Integer::from(0i32).trailing_zeros(), None);
+        assert!(Integer::from(0i32).is_zero());
+        assert_eq!(Integer::from(2i32).pow(100).bits(), 101);
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        assert!(Integer::from(223i32).is_probable_prime());
+        assert!(!Integer::from(57i32).is_probable_prime());
+        assert!(!Integer::from(1i32).is_probable_prime());
+        assert!((Integer::from(2i32).pow(255) - 19i32).is_probable_prime());
+        assert!(!(Integer::from(2i32).pow(256) - 1i32).is_probable_prime());
+    }
+
+    #[test]
+    fn test_sample_below() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let bound = Integer::from(1000i32);
+        for _ in 0..100 {
+            let value = bound.sample_below(&mut rng);
+            assert!(value >= 0 && value < bound);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod big_integer;
+mod primality;
+#[cfg(feature = "pure-rust")]
+pub mod pure_integer;
+#[cfg(all(feature = "rug", not(feature = "pure-rust")))]
+mod rug_integer;
+
+pub use big_integer::{BigInteger, Integer};
@@ -0,0 +1,211 @@
+use super::big_integer::BigInteger;
+
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Baillie-PSW probable prime test: trial division by small primes, a strong
+/// Fermat test to base 2 and a strong Lucas test with Selfridge's parameters.
+/// No composite passing it is known.
+pub(crate) fn is_probable_prime<T: BigInteger>(n: &T) -> bool {
+    if *n < 2 {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if n.rem_u32(p) == 0 {
+            return *n == p as i32;
+        }
+    }
+    // Any composite below 101^2 has a factor up to 97.
+    if *n < 101 * 101 {
+        return true;
+    }
+
+    is_strong_probable_prime(n, &T::from(2i32)) && is_strong_lucas_probable_prime(n)
+}
+
+// Miller-Rabin round for an odd `n` greater than `base`.
+fn is_strong_probable_prime<T: BigInteger>(n: &T, base: &T) -> bool {
+    let n_minus_one = n.clone() - 1i32;
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = n_minus_one.clone() >> s;
+
+    let mut x = base.modpow(&d, n).unwrap();
+    if x == 1i32 || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.clone() * &x % n;
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+// Strong Lucas test with P = 1 and Q = (1 - D) / 4, where D is the first of
+// 5, -7, 9, -11, ... with Jacobi symbol (D/n) = -1. Expects an odd `n` with
+// no factor below 100.
+fn is_strong_lucas_probable_prime<T: BigInteger>(n: &T) -> bool {
+    if is_square(n) {
+        return false;
+    }
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(&T::from(d), n) {
+            -1 => break,
+            0 if *n != T::from(d.unsigned_abs()) => return false,
+            _ => {}
+        }
+        d = if d > 0 { -d - 2 } else { -d + 2 };
+    }
+    let q = T::from((1 - d) / 4).rem_euclid(n);
+    let d = T::from(d).rem_euclid(n);
+
+    let n_plus_one = n.clone() + 1i32;
+    let s = n_plus_one.trailing_zeros().unwrap();
+    let k = n_plus_one >> s;
+
+    // U_1 = 1 and V_1 = P = 1, then walk the bits of k doubling the index and
+    // stepping it by one where needed.
+    let mut u = T::from(1i32);
+    let mut v = T::from(1i32);
+    let mut q_k = q.clone();
+    for i in (0..k.bits() - 1).rev() {
+        u = u * &v % n;
+        v = (v.clone() * &v - q_k.clone() * 2i32).rem_euclid(n);
+        q_k = q_k.clone() * &q_k % n;
+        if k.bit(i) {
+            let next_u = half(u.clone() + &v, n);
+            v = half(d.clone() * &u + v, n);
+            u = next_u;
+            q_k = q_k * &q % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (v.clone() * &v - q_k.clone() * 2i32).rem_euclid(n);
+        if v.is_zero() {
+            return true;
+        }
+        q_k = q_k.clone() * &q_k % n;
+    }
+    false
+}
+
+// `value / 2 mod n` for an odd `n`.
+fn half<T: BigInteger>(value: T, n: &T) -> T {
+    let value = value.rem_euclid(n);
+    if value.is_odd() {
+        (value + n) >> 1
+    } else {
+        value >> 1
+    }
+}
+
+// Jacobi symbol (a/n) for an odd positive `n`.
+fn jacobi<T: BigInteger>(a: &T, n: &T) -> i32 {
+    let mut a = a.rem_euclid(n);
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1;
+            if matches!(n.rem_u32(8), 3 | 5) {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a.rem_u32(4) == 3 && n.rem_u32(4) == 3 {
+            result = -result;
+        }
+        a = a.rem_euclid(&n);
+    }
+
+    if n == 1i32 {
+        result
+    } else {
+        0
+    }
+}
+
+fn is_square<T: BigInteger>(n: &T) -> bool {
+    // Newton's method from a power of two above the square root.
+    let mut root = T::from(1i32) << (n.bits() / 2 + 1);
+    loop {
+        let next = (root.clone() + n.clone() / &root) >> 1;
+        if next >= root {
+            break;
+        }
+        root = next;
+    }
+    root.clone() * &root == *n
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::integer::{BigInteger, Integer};
+
+    use super::*;
+
+    #[test]
+    fn test_small_numbers() {
+        let primes: Vec<u32> = (2..12000u32)
+            .filter(|n| (2..*n).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+            .collect();
+
+        for n in 0..12000u32 {
+            assert_eq!(
+                is_probable_prime(&Integer::from(n)),
+                primes.contains(&n),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        // Strong pseudoprimes to base 2, each caught by the Lucas test.
+        for n in [2047u64, 3277, 4033, 4681, 8321, 3215031751] {
+            let n = Integer::from(n);
+            assert!(is_strong_probable_prime(&n, &Integer::from(2i32)));
+            assert!(!is_strong_lucas_probable_prime(&n));
+            assert!(!is_probable_prime(&n));
+        }
+        // Strong Lucas pseudoprimes, each caught by the base 2 test.
+        for n in [5459u64, 5777, 10877, 16109, 18971] {
+            let n = Integer::from(n);
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!is_strong_probable_prime(&n, &Integer::from(2i32)));
+            assert!(!is_probable_prime(&n));
+        }
+        // Carmichael numbers.
+        for n in [561u64, 1105, 1729, 41041, 825265] {
+            assert!(!is_probable_prime(&Integer::from(n)));
+        }
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let secp256k1_p =
+            Integer::from(2i32).pow(256) - Integer::from(2i32).pow(32) - Integer::from(977i32);
+        let secp256k1_n = Integer::from_radix_str(
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            16,
+        )
+        .unwrap();
+        let mersenne_127 = Integer::from(2i32).pow(127) - 1i32;
+
+        assert!(is_probable_prime(&secp256k1_p));
+        assert!(is_probable_prime(&secp256k1_n));
+        assert!(is_probable_prime(&mersenne_127));
+        assert!(!is_probable_prime(&(secp256k1_p.clone() * &secp256k1_n)));
+        assert!(!is_probable_prime(&(mersenne_127.clone() * &mersenne_127)));
+    }
+}
@@ -0,0 +1,250 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Shl, ShlAssign, Shr,
+    ShrAssign, Sub, SubAssign,
+};
+
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer as _;
+use num_traits::{Euclid, Pow, ToPrimitive, Zero};
+
+use super::big_integer::BigInteger;
+
+/// Arbitrary precision integer implemented in pure Rust on top of
+/// `num-bigint`, with the same operator semantics as `rug::Integer`.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PureInteger(BigInt);
+
+impl BigInteger for PureInteger {
+    fn from_radix_str(src: &str, radix: u32) -> Option<PureInteger> {
+        if !(2..=36).contains(&radix) {
+            return None;
+        }
+        BigInt::parse_bytes(src.as_bytes(), radix).map(PureInteger)
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> PureInteger {
+        PureInteger(BigInt::from_bytes_be(Sign::Plus, bytes))
+    }
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        if self.0.is_zero() {
+            return Vec::new();
+        }
+        self.0.magnitude().to_bytes_be()
+    }
+
+    fn from_le_limbs(limbs: &[u64]) -> PureInteger {
+        let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        PureInteger(BigInt::from_bytes_le(Sign::Plus, &bytes))
+    }
+
+    fn write_le_limbs(&self, limbs: &mut [u64]) {
+        let mut digits = self.0.iter_u64_digits();
+        for limb in limbs.iter_mut() {
+            *limb = digits.next().unwrap_or(0);
+        }
+    }
+
+    fn pow(self, exp: u32) -> PureInteger {
+        PureInteger(Pow::pow(self.0, exp))
+    }
+
+    fn modpow(&self, exp: &PureInteger, modulus: &PureInteger) -> Option<PureInteger> {
+        if exp.0.sign() == Sign::Minus {
+            let inverse = self.mod_inverse(modulus)?;
+            return Some(PureInteger(inverse.0.modpow(&-&exp.0, &modulus.0)));
+        }
+        Some(PureInteger(self.0.modpow(&exp.0, &modulus.0)))
+    }
+
+    fn mod_inverse(&self, modulus: &PureInteger) -> Option<PureInteger> {
+        self.0.modinv(&modulus.0).map(PureInteger)
+    }
+
+    fn rem_euclid(&self, modulus: &PureInteger) -> PureInteger {
+        PureInteger(Euclid::rem_euclid(&self.0, &modulus.0))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    fn is_odd(&self) -> bool {
+        self.0.is_odd()
+    }
+
+    fn is_multiple_of(&self, divisor: &PureInteger) -> bool {
+        if divisor.0.is_zero() {
+            return self.0.is_zero();
+        }
+        (&self.0 % &divisor.0).is_zero()
+    }
+
+    fn rem_u32(&self, modulus: u32) -> u32 {
+        self.0.mod_floor(&BigInt::from(modulus)).to_u32().unwrap()
+    }
+
+    fn bits(&self) -> u32 {
+        self.0.bits() as u32
+    }
+
+    fn bit(&self, index: u32) -> bool {
+        self.0.bit(index as u64)
+    }
+
+    fn trailing_zeros(&self) -> Option<u32> {
+        self.0.trailing_zeros().map(|zeros| zeros as u32)
+    }
+}
+
+impl fmt::Display for PureInteger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for PureInteger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::LowerHex for PureInteger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+macro_rules! from_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PureInteger {
+                fn from(value: $t) -> PureInteger {
+                    PureInteger(BigInt::from(value))
+                }
+            }
+        )*
+    };
+}
+
+from_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<&PureInteger> for PureInteger {
+    fn from(value: &PureInteger) -> PureInteger {
+        value.clone()
+    }
+}
+
+impl PartialEq<i32> for PureInteger {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == BigInt::from(*other)
+    }
+}
+
+impl PartialOrd<i32> for PureInteger {
+    fn partial_cmp(&self, other: &i32) -> Option<Ordering> {
+        self.0.partial_cmp(&BigInt::from(*other))
+    }
+}
+
+macro_rules! binary_op {
+    ($($op:ident, $method:ident, $assign:ident, $assign_method:ident);*) => {
+        $(
+            impl $op for PureInteger {
+                type Output = PureInteger;
+
+                fn $method(self, rhs: PureInteger) -> PureInteger {
+                    PureInteger(self.0.$method(rhs.0))
+                }
+            }
+
+            impl $op<&PureInteger> for PureInteger {
+                type Output = PureInteger;
+
+                fn $method(self, rhs: &PureInteger) -> PureInteger {
+                    PureInteger(self.0.$method(&rhs.0))
+                }
+            }
+
+            impl $op<i32> for PureInteger {
+                type Output = PureInteger;
+
+                fn $method(self, rhs: i32) -> PureInteger {
+                    PureInteger(self.0.$method(rhs))
+                }
+            }
+
+            impl $op<u32> for PureInteger {
+                type Output = PureInteger;
+
+                fn $method(self, rhs: u32) -> PureInteger {
+                    PureInteger(self.0.$method(rhs))
+                }
+            }
+
+            impl $assign for PureInteger {
+                fn $assign_method(&mut self, rhs: PureInteger) {
+                    self.0.$assign_method(rhs.0)
+                }
+            }
+
+            impl $assign<&PureInteger> for PureInteger {
+                fn $assign_method(&mut self, rhs: &PureInteger) {
+                    self.0.$assign_method(&rhs.0)
+                }
+            }
+
+            impl $assign<i32> for PureInteger {
+                fn $assign_method(&mut self, rhs: i32) {
+                    self.0.$assign_method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+binary_op!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+
+impl Neg for PureInteger {
+    type Output = PureInteger;
+
+    fn neg(self) -> PureInteger {
+        PureInteger(-self.0)
+    }
+}
+
+impl Shl<u32> for PureInteger {
+    type Output = PureInteger;
+
+    fn shl(self, rhs: u32) -> PureInteger {
+        PureInteger(self.0 << rhs)
+    }
+}
+
+impl Shr<u32> for PureInteger {
+    type Output = PureInteger;
+
+    fn shr(self, rhs: u32) -> PureInteger {
+        PureInteger(self.0 >> rhs)
+    }
+}
+
+impl ShlAssign<u32> for PureInteger {
+    fn shl_assign(&mut self, rhs: u32) {
+        self.0 <<= rhs;
+    }
+}
+
+impl ShrAssign<u32> for PureInteger {
+    fn shr_assign(&mut self, rhs: u32) {
+        self.0 >>= rhs;
+    }
+}
@@ -0,0 +1,80 @@
+use std::cmp::Ordering;
+
+use rug::{
+    integer::{IsPrime, Order},
+    ops::Pow,
+    Integer,
+};
+
+use super::big_integer::BigInteger;
+
+impl BigInteger for Integer {
+    fn from_radix_str(src: &str, radix: u32) -> Option<Integer> {
+        Integer::from_str_radix(src, radix as i32).ok()
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Integer {
+        Integer::from_digits(bytes, Order::Msf)
+    }
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_digits(Order::Msf)
+    }
+
+    fn from_le_limbs(limbs: &[u64]) -> Integer {
+        Integer::from_digits(limbs, Order::Lsf)
+    }
+
+    fn write_le_limbs(&self, limbs: &mut [u64]) {
+        self.write_digits(limbs, Order::Lsf)
+    }
+
+    fn pow(self, exp: u32) -> Integer {
+        Pow::pow(self, exp)
+    }
+
+    fn modpow(&self, exp: &Integer, modulus: &Integer) -> Option<Integer> {
+        self.pow_mod_ref(exp, modulus).map(Integer::from)
+    }
+
+    fn mod_inverse(&self, modulus: &Integer) -> Option<Integer> {
+        self.invert_ref(modulus).map(Integer::from)
+    }
+
+    fn rem_euclid(&self, modulus: &Integer) -> Integer {
+        <(Integer, Integer)>::from(self.div_rem_euc_ref(modulus)).1
+    }
+
+    fn is_zero(&self) -> bool {
+        self.cmp0() == Ordering::Equal
+    }
+
+    fn is_odd(&self) -> bool {
+        Integer::is_odd(self)
+    }
+
+    fn is_multiple_of(&self, divisor: &Integer) -> bool {
+        Integer::is_divisible(self, divisor)
+    }
+
+    fn rem_u32(&self, modulus: u32) -> u32 {
+        Integer::mod_u(self, modulus)
+    }
+
+    fn bits(&self) -> u32 {
+        Integer::significant_bits(self)
+    }
+
+    fn bit(&self, index: u32) -> bool {
+        Integer::get_bit(self, index)
+    }
+
+    fn trailing_zeros(&self) -> Option<u32> {
+        self.find_one(0)
+    }
+
+    // GMP runs Baillie-PSW followed by `reps - 24` Miller-Rabin rounds.
+    fn is_probable_prime(&self) -> bool {
+        self.is_probably_prime(30) != IsPrime::No
+    }
+}
@@ -1,3 +1,8 @@
 pub mod cryptography;
 pub mod elliptic_curve;
+pub mod encoding;
 pub mod finite_field;
+pub mod integer;
+pub mod network;
+pub mod script;
+pub mod transaction;
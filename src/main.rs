@@ -1,42 +1,47 @@
-use programmingbitcoin::{elliptic_curve::point::Point, finite_field::field_element::FieldElement};
-use rug::ops::*;
-use rug::Integer;
+use programmingbitcoin::{
+    cryptography::private_key::PrivateKey,
+    elliptic_curve::{curve::Curve, point::Point, scalar::Scalar},
+    finite_field::field_element::FieldElement,
+    integer::{BigInteger, Integer},
+    network::Network,
+};
 
 fn main() {
     // CHAPTER 1: Finite Fields
-    // Exercise 2:
-    let a = FieldElement::new(Integer::from(44i32), Integer::from(57i32)).unwrap();
-    let b = FieldElement::new(Integer::from(33i32), Integer::from(57i32)).unwrap();
+    // Exercise 2: 57 is not prime, so these only exercise addition and
+    // subtraction and skip the order check.
+    let a = FieldElement::new_unchecked(Integer::from(44i32), Integer::from(57i32));
+    let b = FieldElement::new_unchecked(Integer::from(33i32), Integer::from(57i32));
 
     assert_eq!(
         a + b,
-        FieldElement::new(Integer::from(20i32), Integer::from(57i32)).unwrap()
+        FieldElement::new_unchecked(Integer::from(20i32), Integer::from(57i32))
     );
 
-    let a = FieldElement::new(Integer::from(9i32), Integer::from(57i32)).unwrap();
-    let b = FieldElement::new(Integer::from(29i32), Integer::from(57i32)).unwrap();
+    let a = FieldElement::new_unchecked(Integer::from(9i32), Integer::from(57i32));
+    let b = FieldElement::new_unchecked(Integer::from(29i32), Integer::from(57i32));
 
     assert_eq!(
         a - b,
-        FieldElement::new(Integer::from(37i32), Integer::from(57i32)).unwrap()
+        FieldElement::new_unchecked(Integer::from(37i32), Integer::from(57i32))
     );
 
-    let a = FieldElement::new(Integer::from(17i32), Integer::from(57i32)).unwrap();
-    let b = FieldElement::new(Integer::from(42i32), Integer::from(57i32)).unwrap();
-    let c = FieldElement::new(Integer::from(49i32), Integer::from(57i32)).unwrap();
+    let a = FieldElement::new_unchecked(Integer::from(17i32), Integer::from(57i32));
+    let b = FieldElement::new_unchecked(Integer::from(42i32), Integer::from(57i32));
+    let c = FieldElement::new_unchecked(Integer::from(49i32), Integer::from(57i32));
 
     assert_eq!(
         a + b + c,
-        FieldElement::new(Integer::from(51i32), Integer::from(57i32)).unwrap()
+        FieldElement::new_unchecked(Integer::from(51i32), Integer::from(57i32))
     );
 
-    let a = FieldElement::new(Integer::from(52i32), Integer::from(57i32)).unwrap();
-    let b = FieldElement::new(Integer::from(30i32), Integer::from(57i32)).unwrap();
-    let c = FieldElement::new(Integer::from(38i32), Integer::from(57i32)).unwrap();
+    let a = FieldElement::new_unchecked(Integer::from(52i32), Integer::from(57i32));
+    let b = FieldElement::new_unchecked(Integer::from(30i32), Integer::from(57i32));
+    let c = FieldElement::new_unchecked(Integer::from(38i32), Integer::from(57i32));
 
     assert_eq!(
         a - b - c,
-        FieldElement::new(Integer::from(41i32), Integer::from(57i32)).unwrap()
+        FieldElement::new_unchecked(Integer::from(41i32), Integer::from(57i32))
     );
 
     // Exercise 4:
@@ -91,45 +96,34 @@ fn main() {
     );
 
     // CHAPTER 3: Elliptic Curve Cryptography
+    let f223 = Curve::f223();
+
     let x1 = FieldElement::new(Integer::from(170i32), Integer::from(223i32)).unwrap();
     let y1 = FieldElement::new(Integer::from(142i32), Integer::from(223i32)).unwrap();
 
     let x2 = FieldElement::new(Integer::from(60i32), Integer::from(223i32)).unwrap();
     let y2 = FieldElement::new(Integer::from(139i32), Integer::from(223i32)).unwrap();
 
-    let p1 = Point::new(
-        x1,
-        y1,
-        FieldElement::new(Integer::from(0i32), Integer::from(223i32)).unwrap(),
-        FieldElement::new(Integer::from(7i32), Integer::from(223i32)).unwrap(),
-    )
-    .unwrap();
-    let p2 = Point::new(
-        x2,
-        y2,
-        FieldElement::new(Integer::from(0i32), Integer::from(223i32)).unwrap(),
-        FieldElement::new(Integer::from(7i32), Integer::from(223i32)).unwrap(),
-    )
-    .unwrap();
+    let p1 = Point::new(x1, y1, &f223).unwrap();
+    let p2 = Point::new(x2, y2, &f223).unwrap();
 
     assert_eq!(
         p1 + p2,
         Point::new(
             FieldElement::new(Integer::from(220i32), Integer::from(223i32)).unwrap(),
             FieldElement::new(Integer::from(181i32), Integer::from(223i32)).unwrap(),
-            FieldElement::new(Integer::from(0i32), Integer::from(223i32)).unwrap(),
-            FieldElement::new(Integer::from(7i32), Integer::from(223i32)).unwrap()
+            &f223
         )
         .unwrap()
     );
 
     // Working with secp256k1:
-    let gx = Integer::from_str_radix(
+    let gx = Integer::from_radix_str(
         "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
         16,
     )
     .unwrap();
-    let gy = Integer::from_str_radix(
+    let gy = Integer::from_radix_str(
         "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
         16,
     )
@@ -138,7 +132,7 @@ fn main() {
 
     assert_eq!(gy.clone().pow(2) % &p, (gx.clone().pow(3) + 7i32) % &p);
 
-    let n = Integer::from_str_radix(
+    let n = Integer::from_radix_str(
         "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
         16,
     )
@@ -146,29 +140,53 @@ fn main() {
 
     println!("{}", n);
 
+    let secp256k1 = Curve::secp256k1();
+    assert_eq!(secp256k1.prime(), &p);
+    assert_eq!(secp256k1.order(), &n);
+
     let x = FieldElement::new(gx, p.clone()).unwrap();
-    let y = FieldElement::new(gy, p.clone()).unwrap();
-    let seven = FieldElement::new(Integer::from(7i32), p.clone()).unwrap();
-    let zero = FieldElement::new(Integer::from(0i32), p).unwrap();
+    let y = FieldElement::new(gy, p).unwrap();
 
-    let _g = Point::new(x, y, zero, seven).unwrap();
+    let g = Point::new(x, y, &secp256k1).unwrap();
+    assert_eq!(g, Point::g_point());
+    assert_eq!(n * g, Point::infinity(&secp256k1));
 
-    let gx = Integer::from_str_radix(
-        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
-        16,
-    )
-    .unwrap();
-    let gy = Integer::from_str_radix(
-        "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
-        16,
-    )
-    .unwrap();
+    // CHAPTER 4: Serialization
+    // Exercise 5:
+    let pk = PrivateKey::new(Scalar::from(5002u32)).unwrap();
+    assert_eq!(
+        pk.point().address(false, Network::Testnet),
+        "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA"
+    );
 
-    let p = Integer::from(2i32).pow(256) - Integer::from(2i32).pow(32) - Integer::from(977i32);
-    let a = FieldElement::new(Integer::from(0i32), p.clone()).unwrap();
-    let b = FieldElement::new(Integer::from(7i32), p.clone()).unwrap();
-    let x = FieldElement::new(gx, p.clone()).unwrap();
-    let y = FieldElement::new(gy, p).unwrap();
+    let pk = PrivateKey::new(Scalar::new(Integer::from(2020i32).pow(5)).unwrap()).unwrap();
+    assert_eq!(
+        pk.point().address(true, Network::Testnet),
+        "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH"
+    );
+
+    let pk = PrivateKey::new(Scalar::new(Integer::from(0x12345deadbeefi64)).unwrap()).unwrap();
+    assert_eq!(
+        pk.point().address(true, Network::Mainnet),
+        "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1"
+    );
 
-    let _s_256_point = Point::new(x, y, a, b);
+    // Exercise 6:
+    let pk = PrivateKey::new(Scalar::from(5003u32)).unwrap();
+    assert_eq!(
+        pk.wif(true, Network::Testnet),
+        "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK"
+    );
+
+    let pk = PrivateKey::new(Scalar::new(Integer::from(2021i32).pow(5)).unwrap()).unwrap();
+    assert_eq!(
+        pk.wif(false, Network::Testnet),
+        "91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic"
+    );
+
+    let pk = PrivateKey::new(Scalar::new(Integer::from(0x54321deadbeefi64)).unwrap()).unwrap();
+    assert_eq!(
+        pk.wif(true, Network::Mainnet),
+        "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a"
+    );
 }
@@ -0,0 +1,37 @@
+/// The Bitcoin networks, which differ in the version bytes of their
+/// encodings.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// Version byte of P2PKH addresses.
+    pub fn p2pkh_prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            Network::Testnet | Network::Signet | Network::Regtest => 0x6f,
+        }
+    }
+
+    /// Version byte of WIF private keys.
+    pub fn wif_prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            Network::Testnet | Network::Signet | Network::Regtest => 0xef,
+        }
+    }
+
+    /// The network of a WIF version byte. The test networks share theirs,
+    /// which maps to `Testnet`.
+    pub fn from_wif_prefix(prefix: u8) -> Option<Network> {
+        match prefix {
+            0x80 => Some(Network::Mainnet),
+            0xef => Some(Network::Testnet),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::number::encode_num;
+use super::opcode::{OP_0, OP_1, OP_16, OP_1NEGATE, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Instruction {
+    /// Pushes `data`. `opcode` is how the push was encoded: the data length
+    /// itself for up to 75 bytes, or `OP_PUSHDATA1`, `OP_PUSHDATA2` or
+    /// `OP_PUSHDATA4` followed by the length.
+    Push { opcode: u8, data: Vec<u8> },
+    /// Any opcode above `OP_PUSHDATA4`.
+    Op(u8),
+}
+
+impl Instruction {
+    /// Pushes `data` with the shortest push opcode.
+    pub fn push(data: Vec<u8>) -> Instruction {
+        let opcode = match data.len() {
+            len @ 0..=0x4b => len as u8,
+            0x4c..=0xff => OP_PUSHDATA1,
+            0x100..=0xffff => OP_PUSHDATA2,
+            _ => OP_PUSHDATA4,
+        };
+        Instruction::Push { opcode, data }
+    }
+
+    /// Pushes `value` with `OP_0`, `OP_1NEGATE` and `OP_1` to `OP_16` for the
+    /// numbers they stand for, and as a script number otherwise.
+    pub fn push_num(value: i64) -> Instruction {
+        match value {
+            0 => Instruction::Push {
+                opcode: OP_0,
+                data: Vec::new(),
+            },
+            -1 => Instruction::Op(OP_1NEGATE),
+            1..=16 => Instruction::Op(OP_1 + value as u8 - 1),
+            _ => Instruction::push(encode_num(value)),
+        }
+    }
+
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Instruction::Push { opcode, .. } | Instruction::Op(opcode) => *opcode,
+        }
+    }
+
+    /// The small integer an `OP_1NEGATE` or `OP_1` to `OP_16` stands for.
+    pub fn small_int(&self) -> Option<i64> {
+        match self {
+            Instruction::Op(OP_1NEGATE) => Some(-1),
+            Instruction::Op(opcode @ OP_1..=OP_16) => Some((opcode - OP_1 + 1) as i64),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        for (len, opcode) in [
+            (0, OP_0),
+            (1, 0x01),
+            (75, 0x4b),
+            (76, OP_PUSHDATA1),
+            (255, OP_PUSHDATA1),
+            (256, OP_PUSHDATA2),
+            (65535, OP_PUSHDATA2),
+            (65536, OP_PUSHDATA4),
+        ] {
+            assert_eq!(Instruction::push(vec![0; len]).opcode(), opcode, "{}", len);
+        }
+    }
+
+    #[test]
+    fn test_push_num() {
+        assert_eq!(Instruction::push_num(0), Instruction::push(Vec::new()));
+        assert_eq!(Instruction::push_num(-1), Instruction::Op(OP_1NEGATE));
+        assert_eq!(Instruction::push_num(16), Instruction::Op(OP_16));
+        assert_eq!(Instruction::push_num(17), Instruction::push(vec![0x11]));
+        assert_eq!(Instruction::push_num(-2), Instruction::push(vec![0x82]));
+
+        for value in -1..=16 {
+            if value != 0 {
+                assert_eq!(Instruction::push_num(value).small_int(), Some(value));
+            }
+        }
+        assert_eq!(Instruction::push_num(0).small_int(), None);
+    }
+}
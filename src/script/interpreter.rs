@@ -0,0 +1,1027 @@
+use std::fmt;
+
+use crate::cryptography::hashing::{hash160, hash256, ripemd160, sha1, sha256};
+use crate::cryptography::signature::{Signature, SignatureError};
+use crate::transaction::tx::{SIGHASH_ALL, SIGHASH_ANYONECANPAY, SIGHASH_SINGLE};
+
+use super::instruction::Instruction;
+use super::number::{decode_num, encode_num};
+use super::opcode::{self, *};
+use super::program::Script;
+use super::signature_checker::SignatureChecker;
+
+const MAX_SCRIPT_SIZE: usize = 10000;
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+const MAX_OPS_PER_SCRIPT: usize = 201;
+const MAX_STACK_SIZE: usize = 1000;
+const MAX_PUBKEYS_PER_MULTISIG: i64 = 20;
+// Arithmetic operands are at most 4 bytes, results may grow to 5.
+const MAX_NUM_SIZE: usize = 4;
+
+/// Script verification rules beyond the base consensus rules, named after
+/// the Bitcoin Core flags they mirror.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct VerifyFlags {
+    /// BIP16: when the script pubkey is P2SH, the script sig must be push
+    /// only and its last push is run as the redeem script.
+    pub p2sh: bool,
+    /// Signatures must be strict DER with a defined hash type and public keys
+    /// compressed or uncompressed SEC.
+    pub strict_encoding: bool,
+}
+
+/// Why a script failed, each variant matching the Bitcoin Core error of the
+/// same name. The message says which instruction failed and shows the stack
+/// at that point.
+#[derive(PartialEq, Debug)]
+pub enum InterpreterError {
+    EvalFalse(String),
+    OpReturn(String),
+    ScriptSize(String),
+    PushSize(String),
+    OpCount(String),
+    StackSize(String),
+    SigCount(String),
+    PubkeyCount(String),
+    Verify(String),
+    EqualVerify(String),
+    CheckMultisigVerify(String),
+    CheckSigVerify(String),
+    NumEqualVerify(String),
+    BadOpcode(String),
+    DisabledOpcode(String),
+    InvalidStackOperation(String),
+    InvalidAltstackOperation(String),
+    UnbalancedConditional(String),
+    SigHashType(String),
+    SigDer(String),
+    PubkeyType(String),
+    SigPushOnly(String),
+    /// A number operand longer than 4 bytes, which Bitcoin Core reports as
+    /// `UNKNOWN_ERROR`.
+    NumberOverflow(String),
+}
+
+/// Runs `script_sig` and then `script_pubkey` on the resulting stack and
+/// succeeds if the top of the stack is true, running the redeem script too
+/// for P2SH outputs when `flags.p2sh` is set.
+///
+/// This is the pre-segwit verification: witness programs are not run and
+/// the locktime opcodes act as the NOPs they replaced.
+pub fn verify_script<C: SignatureChecker>(
+    script_sig: &Script,
+    script_pubkey: &Script,
+    flags: VerifyFlags,
+    checker: &C,
+) -> Result<(), InterpreterError> {
+    let mut stack = Vec::new();
+    eval_script(&mut stack, script_sig, flags, checker)?;
+    let p2sh_stack = stack.clone();
+    eval_script(&mut stack, script_pubkey, flags, checker)?;
+    check_result(&stack)?;
+
+    if flags.p2sh && script_pubkey.is_p2sh() {
+        if !script_sig.is_push_only() {
+            return Err(InterpreterError::SigPushOnly(String::from(
+                "script sig of a P2SH spend pushes more than data",
+            )));
+        }
+
+        let mut stack = p2sh_stack;
+        // Not empty, as the script pubkey hashed the top element.
+        let serialized = stack.pop().expect("the redeem script");
+        let redeem_script = Script::from_bytes(&serialized);
+        eval_script(&mut stack, &redeem_script, flags, checker)?;
+        check_result(&stack)?;
+    }
+    Ok(())
+}
+
+/// Runs `script` on `stack`, leaving the resulting stack in place.
+pub fn eval_script<C: SignatureChecker>(
+    stack: &mut Vec<Vec<u8>>,
+    script: &Script,
+    flags: VerifyFlags,
+    checker: &C,
+) -> Result<(), InterpreterError> {
+    if script.serialize().len() > MAX_SCRIPT_SIZE {
+        return Err(InterpreterError::ScriptSize(format!(
+            "script is larger than {} bytes",
+            MAX_SCRIPT_SIZE
+        )));
+    }
+
+    let mut machine = Machine {
+        stack,
+        altstack: Vec::new(),
+        conditions: Vec::new(),
+        op_count: 0,
+        code_start: 0,
+        position: 0,
+        script,
+        flags,
+        checker,
+    };
+    for (position, instruction) in script.instructions().iter().enumerate() {
+        machine.position = position;
+        machine.step(instruction)?;
+    }
+    // Like any other opcode, a push that cannot be read fails even in a
+    // branch that is not taken, but only once it is reached.
+    if script.truncated_push().is_some() {
+        machine.position = script.instructions().len();
+        return Err(machine.error(
+            InterpreterError::BadOpcode,
+            "push past the end of the script",
+        ));
+    }
+
+    if !machine.conditions.is_empty() {
+        return Err(machine.error(
+            InterpreterError::UnbalancedConditional,
+            "OP_IF without OP_ENDIF",
+        ));
+    }
+    Ok(())
+}
+
+/// Whether a stack element counts as true: any non-zero byte, except for a
+/// lone sign bit in the last byte, which is negative zero.
+pub fn cast_to_bool(element: &[u8]) -> bool {
+    match element.split_last() {
+        Some((&last, rest)) => rest.iter().any(|&b| b != 0) || (last & 0x7f) != 0,
+        None => false,
+    }
+}
+
+fn check_result(stack: &[Vec<u8>]) -> Result<(), InterpreterError> {
+    match stack.last() {
+        Some(top) if cast_to_bool(top) => Ok(()),
+        _ => Err(InterpreterError::EvalFalse(format!(
+            "script finished with a false or empty stack: {}",
+            format_stack(stack)
+        ))),
+    }
+}
+
+struct Machine<'a, C> {
+    stack: &'a mut Vec<Vec<u8>>,
+    altstack: Vec<Vec<u8>>,
+    // Whether each enclosing OP_IF branch is being executed.
+    conditions: Vec<bool>,
+    op_count: usize,
+    // Index of the instruction after the last OP_CODESEPARATOR.
+    code_start: usize,
+    position: usize,
+    script: &'a Script,
+    flags: VerifyFlags,
+    checker: &'a C,
+}
+
+impl<C: SignatureChecker> Machine<'_, C> {
+    fn step(&mut self, instruction: &Instruction) -> Result<(), InterpreterError> {
+        let executing = self.conditions.iter().all(|&condition| condition);
+        let opcode = instruction.opcode();
+
+        if let Instruction::Push { data, .. } = instruction {
+            if data.len() > MAX_SCRIPT_ELEMENT_SIZE {
+                return Err(self.error(
+                    InterpreterError::PushSize,
+                    &format!("push of {} bytes", data.len()),
+                ));
+            }
+        }
+        if opcode > OP_16 {
+            self.op_count += 1;
+            if self.op_count > MAX_OPS_PER_SCRIPT {
+                return Err(self.error(
+                    InterpreterError::OpCount,
+                    &format!("more than {} opcodes", MAX_OPS_PER_SCRIPT),
+                ));
+            }
+        }
+        // Disabled opcodes fail even in a branch that is not taken.
+        if is_disabled(opcode) {
+            return Err(self.error(InterpreterError::DisabledOpcode, "disabled opcode"));
+        }
+
+        match instruction {
+            Instruction::Push { data, .. } if executing => self.stack.push(data.clone()),
+            Instruction::Op(opcode) if executing || (OP_IF..=OP_ENDIF).contains(opcode) => {
+                self.execute(*opcode, executing)?
+            }
+            _ => {}
+        }
+
+        if self.stack.len() + self.altstack.len() > MAX_STACK_SIZE {
+            return Err(self.error(
+                InterpreterError::StackSize,
+                &format!("more than {} stack elements", MAX_STACK_SIZE),
+            ));
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, opcode: u8, executing: bool) -> Result<(), InterpreterError> {
+        match opcode {
+            OP_1NEGATE | OP_1..=OP_16 => {
+                let value = Instruction::Op(opcode).small_int().unwrap();
+                self.stack.push(encode_num(value));
+            }
+
+            // The locktime opcodes keep their NOP meaning without the
+            // BIP65 and BIP112 rules.
+            OP_NOP
+            | OP_NOP1
+            | OP_CHECKLOCKTIMEVERIFY
+            | OP_CHECKSEQUENCEVERIFY
+            | OP_NOP4..=OP_NOP10 => {}
+
+            OP_IF | OP_NOTIF => {
+                let mut condition = false;
+                if executing {
+                    if self.stack.is_empty() {
+                        return Err(self.error(
+                            InterpreterError::UnbalancedConditional,
+                            "no condition on the stack",
+                        ));
+                    }
+                    condition = cast_to_bool(&self.pop()) == (opcode == OP_IF);
+                }
+                self.conditions.push(condition);
+            }
+            OP_ELSE => match self.conditions.last_mut() {
+                Some(condition) => *condition = !*condition,
+                None => {
+                    return Err(self.error(
+                        InterpreterError::UnbalancedConditional,
+                        "OP_ELSE without OP_IF",
+                    ))
+                }
+            },
+            OP_ENDIF => {
+                if self.conditions.pop().is_none() {
+                    return Err(self.error(
+                        InterpreterError::UnbalancedConditional,
+                        "OP_ENDIF without OP_IF",
+                    ));
+                }
+            }
+            OP_VERIFY => {
+                self.require(1)?;
+                if !cast_to_bool(self.top(1)) {
+                    return Err(self.error(InterpreterError::Verify, "false value"));
+                }
+                self.pop();
+            }
+            OP_RETURN => return Err(self.error(InterpreterError::OpReturn, "OP_RETURN")),
+
+            OP_TOALTSTACK => {
+                self.require(1)?;
+                let element = self.pop();
+                self.altstack.push(element);
+            }
+            OP_FROMALTSTACK => {
+                let element = self.altstack.pop().ok_or_else(|| {
+                    self.error(
+                        InterpreterError::InvalidAltstackOperation,
+                        "empty alt stack",
+                    )
+                })?;
+                self.stack.push(element);
+            }
+            OP_2DROP => {
+                self.require(2)?;
+                self.pop();
+                self.pop();
+            }
+            OP_2DUP => self.copy(&[2, 1])?,
+            OP_3DUP => self.copy(&[3, 2, 1])?,
+            OP_2OVER => self.copy(&[4, 3])?,
+            OP_2ROT => {
+                self.require(6)?;
+                let len = self.stack.len();
+                let moved: Vec<_> = self.stack.drain(len - 6..len - 4).collect();
+                self.stack.extend(moved);
+            }
+            OP_2SWAP => {
+                self.require(4)?;
+                let len = self.stack.len();
+                self.stack[len - 4..].rotate_left(2);
+            }
+            OP_IFDUP => {
+                self.require(1)?;
+                if cast_to_bool(self.top(1)) {
+                    self.copy(&[1])?;
+                }
+            }
+            OP_DEPTH => self.stack.push(encode_num(self.stack.len() as i64)),
+            OP_DROP => {
+                self.require(1)?;
+                self.pop();
+            }
+            OP_DUP => self.copy(&[1])?,
+            OP_NIP => {
+                self.require(2)?;
+                let len = self.stack.len();
+                self.stack.remove(len - 2);
+            }
+            OP_OVER => self.copy(&[2])?,
+            OP_PICK | OP_ROLL => {
+                self.require(2)?;
+                let n = self.num(1)?;
+                self.pop();
+                if n < 0 || n as usize >= self.stack.len() {
+                    return Err(self.error(
+                        InterpreterError::InvalidStackOperation,
+                        &format!("cannot reach stack element {}", n),
+                    ));
+                }
+                let index = self.stack.len() - 1 - n as usize;
+                let element = match opcode {
+                    OP_PICK => self.stack[index].clone(),
+                    _ => self.stack.remove(index),
+                };
+                self.stack.push(element);
+            }
+            OP_ROT => {
+                self.require(3)?;
+                let len = self.stack.len();
+                self.stack[len - 3..].rotate_left(1);
+            }
+            OP_SWAP => {
+                self.require(2)?;
+                let len = self.stack.len();
+                self.stack.swap(len - 2, len - 1);
+            }
+            OP_TUCK => {
+                self.require(2)?;
+                let top = self.top(1).clone();
+                let len = self.stack.len();
+                self.stack.insert(len - 2, top);
+            }
+            OP_SIZE => {
+                self.require(1)?;
+                let size = self.top(1).len();
+                self.stack.push(encode_num(size as i64));
+            }
+
+            OP_EQUAL | OP_EQUALVERIFY => {
+                self.require(2)?;
+                let equal = self.pop() == self.pop();
+                self.push_bool(equal);
+                if opcode == OP_EQUALVERIFY {
+                    self.verify(InterpreterError::EqualVerify)?;
+                }
+            }
+
+            OP_1ADD | OP_1SUB | OP_NEGATE | OP_ABS | OP_NOT | OP_0NOTEQUAL => {
+                self.require(1)?;
+                let a = self.num(1)?;
+                self.pop();
+                self.stack.push(encode_num(match opcode {
+                    OP_1ADD => a + 1,
+                    OP_1SUB => a - 1,
+                    OP_NEGATE => -a,
+                    OP_ABS => a.abs(),
+                    OP_NOT => (a == 0) as i64,
+                    _ => (a != 0) as i64,
+                }));
+            }
+            OP_ADD
+            | OP_SUB
+            | OP_BOOLAND
+            | OP_BOOLOR
+            | OP_NUMEQUAL
+            | OP_NUMEQUALVERIFY
+            | OP_NUMNOTEQUAL
+            | OP_LESSTHAN
+            | OP_GREATERTHAN
+            | OP_LESSTHANOREQUAL
+            | OP_GREATERTHANOREQUAL
+            | OP_MIN
+            | OP_MAX => {
+                self.require(2)?;
+                let (a, b) = (self.num(2)?, self.num(1)?);
+                self.pop();
+                self.pop();
+                self.stack.push(encode_num(match opcode {
+                    OP_ADD => a + b,
+                    OP_SUB => a - b,
+                    OP_BOOLAND => (a != 0 && b != 0) as i64,
+                    OP_BOOLOR => (a != 0 || b != 0) as i64,
+                    OP_NUMEQUAL | OP_NUMEQUALVERIFY => (a == b) as i64,
+                    OP_NUMNOTEQUAL => (a != b) as i64,
+                    OP_LESSTHAN => (a < b) as i64,
+                    OP_GREATERTHAN => (a > b) as i64,
+                    OP_LESSTHANOREQUAL => (a <= b) as i64,
+                    OP_GREATERTHANOREQUAL => (a >= b) as i64,
+                    OP_MIN => a.min(b),
+                    _ => a.max(b),
+                }));
+                if opcode == OP_NUMEQUALVERIFY {
+                    self.verify(InterpreterError::NumEqualVerify)?;
+                }
+            }
+            OP_WITHIN => {
+                self.require(3)?;
+                let (x, min, max) = (self.num(3)?, self.num(2)?, self.num(1)?);
+                self.stack.truncate(self.stack.len() - 3);
+                self.push_bool(min <= x && x < max);
+            }
+
+            OP_RIPEMD160 | OP_SHA1 | OP_SHA256 | OP_HASH160 | OP_HASH256 => {
+                self.require(1)?;
+                let data = self.pop();
+                self.stack.push(match opcode {
+                    OP_RIPEMD160 => ripemd160(&data).to_vec(),
+                    OP_SHA1 => sha1(&data).to_vec(),
+                    OP_SHA256 => sha256(&data).to_vec(),
+                    OP_HASH160 => hash160(&data).to_vec(),
+                    _ => hash256(&data).to_vec(),
+                });
+            }
+            OP_CODESEPARATOR => self.code_start = self.position + 1,
+            OP_CHECKSIG | OP_CHECKSIGVERIFY => {
+                self.require(2)?;
+                let signature = self.top(2).clone();
+                let pubkey = self.top(1).clone();
+
+                let script_code = self.script_code(std::slice::from_ref(&signature));
+                self.check_signature_encoding(&signature)?;
+                self.check_pubkey_encoding(&pubkey)?;
+                let valid =
+                    self.checker
+                        .check_signature(&signature, &pubkey, &script_code, self.flags);
+
+                self.pop();
+                self.pop();
+                self.push_bool(valid);
+                if opcode == OP_CHECKSIGVERIFY {
+                    self.verify(InterpreterError::CheckSigVerify)?;
+                }
+            }
+            OP_CHECKMULTISIG | OP_CHECKMULTISIGVERIFY => {
+                self.check_multisig()?;
+                if opcode == OP_CHECKMULTISIGVERIFY {
+                    self.verify(InterpreterError::CheckMultisigVerify)?;
+                }
+            }
+
+            _ => {
+                return Err(self.error(
+                    InterpreterError::BadOpcode,
+                    &format!("{} is not a valid opcode", opcode::name(opcode)),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    // Follows Bitcoin Core, including popping one more element than the
+    // signatures and keys.
+    fn check_multisig(&mut self) -> Result<(), InterpreterError> {
+        let mut i = 1;
+        self.require(i)?;
+        let mut key_count = self.num(i)?;
+        if !(0..=MAX_PUBKEYS_PER_MULTISIG).contains(&key_count) {
+            return Err(self.error(
+                InterpreterError::PubkeyCount,
+                &format!("{} public keys", key_count),
+            ));
+        }
+        self.op_count += key_count as usize;
+        if self.op_count > MAX_OPS_PER_SCRIPT {
+            return Err(self.error(
+                InterpreterError::OpCount,
+                &format!("more than {} opcodes", MAX_OPS_PER_SCRIPT),
+            ));
+        }
+        i += 1;
+        let mut key = i;
+        i += key_count as usize;
+
+        self.require(i)?;
+        let mut sig_count = self.num(i)?;
+        if !(0..=key_count).contains(&sig_count) {
+            return Err(self.error(
+                InterpreterError::SigCount,
+                &format!("{} signatures for {} keys", sig_count, key_count),
+            ));
+        }
+        i += 1;
+        let mut sig = i;
+        i += sig_count as usize;
+        self.require(i)?;
+
+        let signatures: Vec<_> = (0..sig_count as usize)
+            .map(|k| self.top(sig + k).clone())
+            .collect();
+        let script_code = self.script_code(&signatures);
+
+        // Signatures have to match keys in order, so give up once fewer keys
+        // remain than signatures.
+        let mut success = true;
+        while success && sig_count > 0 {
+            let signature = self.top(sig).clone();
+            let pubkey = self.top(key).clone();
+            self.check_signature_encoding(&signature)?;
+            self.check_pubkey_encoding(&pubkey)?;
+
+            if self
+                .checker
+                .check_signature(&signature, &pubkey, &script_code, self.flags)
+            {
+                sig += 1;
+                sig_count -= 1;
+            }
+            key += 1;
+            key_count -= 1;
+            if sig_count > key_count {
+                success = false;
+            }
+        }
+
+        self.stack.truncate(self.stack.len() - (i - 1));
+        // The extra element, which an off-by-one error in the original
+        // implementation consumes.
+        if self.stack.pop().is_none() {
+            return Err(self.error(
+                InterpreterError::InvalidStackOperation,
+                "missing the extra OP_CHECKMULTISIG element",
+            ));
+        }
+        self.push_bool(success);
+        Ok(())
+    }
+
+    // The script from the last OP_CODESEPARATOR on, without pushes of the
+    // signatures being checked, which cannot sign themselves.
+    fn script_code(&self, signatures: &[Vec<u8>]) -> Script {
+        let pushes: Vec<_> = signatures
+            .iter()
+            .map(|signature| Instruction::push(signature.clone()))
+            .collect();
+        let instructions = self.script.instructions()[self.code_start..]
+            .iter()
+            .filter(|instruction| !pushes.contains(instruction))
+            .cloned()
+            .collect();
+        Script::new(instructions).expect("instructions of a valid script")
+    }
+
+    fn check_signature_encoding(&self, signature: &[u8]) -> Result<(), InterpreterError> {
+        // An empty signature is allowed as a compact way to fail a check.
+        let Some((&hash_type, der)) = signature.split_last() else {
+            return Ok(());
+        };
+        if !self.flags.strict_encoding {
+            return Ok(());
+        }
+
+        // Integers out of range are strict DER, they just cannot verify.
+        match Signature::parse_der(der) {
+            Ok(_) | Err(SignatureError::IntegerOutOfRange(_)) => {}
+            Err(e) => {
+                return Err(self.error(
+                    InterpreterError::SigDer,
+                    &format!("signature is not strict DER: {}", e),
+                ))
+            }
+        }
+        let base_type = (hash_type as u32) & !SIGHASH_ANYONECANPAY;
+        if !(SIGHASH_ALL..=SIGHASH_SINGLE).contains(&base_type) {
+            return Err(self.error(
+                InterpreterError::SigHashType,
+                &format!("undefined hash type {:#04x}", hash_type),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_pubkey_encoding(&self, pubkey: &[u8]) -> Result<(), InterpreterError> {
+        let valid = matches!(
+            (pubkey.first(), pubkey.len()),
+            (Some(0x02 | 0x03), 33) | (Some(0x04), 65)
+        );
+        if self.flags.strict_encoding && !valid {
+            return Err(self.error(
+                InterpreterError::PubkeyType,
+                "public key is not compressed or uncompressed SEC",
+            ));
+        }
+        Ok(())
+    }
+
+    fn require(&self, count: usize) -> Result<(), InterpreterError> {
+        if self.stack.len() < count {
+            return Err(self.error(
+                InterpreterError::InvalidStackOperation,
+                &format!("needs {} stack elements", count),
+            ));
+        }
+        Ok(())
+    }
+
+    // The element `depth` places from the top, 1 being the top itself.
+    fn top(&self, depth: usize) -> &Vec<u8> {
+        &self.stack[self.stack.len() - depth]
+    }
+
+    fn pop(&mut self) -> Vec<u8> {
+        self.stack.pop().expect("stack size checked")
+    }
+
+    // Pushes copies of the elements at `depths`, counted before any is pushed.
+    fn copy(&mut self, depths: &[usize]) -> Result<(), InterpreterError> {
+        self.require(depths[0])?;
+        let len = self.stack.len();
+        for &depth in depths {
+            let element = self.stack[len - depth].clone();
+            self.stack.push(element);
+        }
+        Ok(())
+    }
+
+    fn num(&self, depth: usize) -> Result<i64, InterpreterError> {
+        let element = self.top(depth);
+        if element.len() > MAX_NUM_SIZE {
+            return Err(self.error(
+                InterpreterError::NumberOverflow,
+                &format!("{} byte number", element.len()),
+            ));
+        }
+        Ok(decode_num(element))
+    }
+
+    fn push_bool(&mut self, value: bool) {
+        self.stack.push(if value { vec![1] } else { Vec::new() });
+    }
+
+    // Pops the result of an opcode ending in VERIFY, failing if it is false.
+    fn verify(&mut self, variant: fn(String) -> InterpreterError) -> Result<(), InterpreterError> {
+        if !cast_to_bool(self.top(1)) {
+            return Err(self.error(variant, "check failed"));
+        }
+        self.pop();
+        Ok(())
+    }
+
+    fn error(&self, variant: fn(String) -> InterpreterError, reason: &str) -> InterpreterError {
+        let opcode = match self.script.instructions().get(self.position) {
+            Some(instruction) => instruction.opcode(),
+            None => self.script.truncated_push().expect("a truncated push")[0],
+        };
+        variant(format!(
+            "{} at instruction {} ({}), stack: {}",
+            reason,
+            self.position,
+            opcode::name(opcode),
+            format_stack(self.stack)
+        ))
+    }
+}
+
+fn is_disabled(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        OP_CAT
+            | OP_SUBSTR
+            | OP_LEFT
+            | OP_RIGHT
+            | OP_INVERT
+            | OP_AND
+            | OP_OR
+            | OP_XOR
+            | OP_2MUL
+            | OP_2DIV
+            | OP_MUL
+            | OP_DIV
+            | OP_MOD
+            | OP_LSHIFT
+            | OP_RSHIFT
+    )
+}
+
+fn format_stack(stack: &[Vec<u8>]) -> String {
+    let elements: Vec<String> = stack.iter().map(hex::encode).collect();
+    format!("[{}]", elements.join(", "))
+}
+
+impl fmt::Display for InterpreterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterpreterError::EvalFalse(message)
+            | InterpreterError::OpReturn(message)
+            | InterpreterError::ScriptSize(message)
+            | InterpreterError::PushSize(message)
+            | InterpreterError::OpCount(message)
+            | InterpreterError::StackSize(message)
+            | InterpreterError::SigCount(message)
+            | InterpreterError::PubkeyCount(message)
+            | InterpreterError::Verify(message)
+            | InterpreterError::EqualVerify(message)
+            | InterpreterError::CheckMultisigVerify(message)
+            | InterpreterError::CheckSigVerify(message)
+            | InterpreterError::NumEqualVerify(message)
+            | InterpreterError::BadOpcode(message)
+            | InterpreterError::DisabledOpcode(message)
+            | InterpreterError::InvalidStackOperation(message)
+            | InterpreterError::InvalidAltstackOperation(message)
+            | InterpreterError::UnbalancedConditional(message)
+            | InterpreterError::SigHashType(message)
+            | InterpreterError::SigDer(message)
+            | InterpreterError::PubkeyType(message)
+            | InterpreterError::SigPushOnly(message)
+            | InterpreterError::NumberOverflow(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for InterpreterError {}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::script::signature_checker::TransactionSignatureChecker;
+    use crate::transaction::outpoint::OutPoint;
+    use crate::transaction::tx::Tx;
+    use crate::transaction::tx_in::TxIn;
+    use crate::transaction::tx_out::TxOut;
+
+    const SCRIPT_TESTS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/script_tests.json"
+    ));
+
+    // Number of rows of script_tests.json that run and that are skipped, to
+    // be updated together with the file.
+    const SCRIPT_TESTS_RUN: usize = 212;
+    const SCRIPT_TESTS_SKIPPED: usize = 2;
+
+    // Rows that start with a witness, and rows with flags the interpreter
+    // does not implement, are skipped, so Bitcoin Core's own file can be used
+    // as is. Every other row must give the expected result.
+    #[test]
+    fn test_script_tests() {
+        let tests: Value = serde_json::from_str(SCRIPT_TESTS).unwrap();
+        let mut run = 0;
+        let mut skipped = BTreeMap::<String, usize>::new();
+
+        for test in tests.as_array().unwrap() {
+            let test = test.as_array().unwrap();
+            // Comments are rows of a single string.
+            if test.len() < 4 {
+                continue;
+            }
+            if test[0].is_array() {
+                let flags = test[3].as_str().unwrap_or_default();
+                *skipped.entry(format!("{} (witness)", flags)).or_default() += 1;
+                continue;
+            }
+            let field = |i: usize| test[i].as_str().unwrap();
+            let Some(flags) = parse_flags(field(2)) else {
+                *skipped.entry(field(2).to_string()).or_default() += 1;
+                continue;
+            };
+            let result = run_script_test(field(0), field(1), flags);
+            let actual = match &result {
+                Ok(()) => "OK",
+                Err(e) => core_name(e),
+            };
+            assert_eq!(actual, field(3), "{:?}: {:?}", test, result);
+            run += 1;
+        }
+
+        for (flags, rows) in &skipped {
+            eprintln!("skipped {} rows with flags {}", rows, flags);
+        }
+        assert_eq!(run, SCRIPT_TESTS_RUN);
+        assert_eq!(skipped.values().sum::<usize>(), SCRIPT_TESTS_SKIPPED);
+    }
+
+    #[test]
+    fn test_cast_to_bool() {
+        assert!(!cast_to_bool(&[]));
+        assert!(!cast_to_bool(&[0]));
+        assert!(!cast_to_bool(&[0, 0]));
+        assert!(!cast_to_bool(&[0x80]));
+        assert!(!cast_to_bool(&[0, 0, 0x80]));
+        assert!(cast_to_bool(&[1]));
+        assert!(cast_to_bool(&[0x80, 0]));
+        assert!(cast_to_bool(&[0, 0x81]));
+    }
+
+    #[test]
+    fn test_error_message() {
+        let script = Script::from_asm("1 2 OP_ADD 4 OP_EQUALVERIFY").unwrap();
+        let error = eval_script(
+            &mut Vec::new(),
+            &script,
+            VerifyFlags::default(),
+            &NoSignatures,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error,
+            InterpreterError::EqualVerify(String::from(
+                "check failed at instruction 4 (OP_EQUALVERIFY), stack: []"
+            ))
+        );
+        assert_eq!(
+            error.to_string(),
+            "check failed at instruction 4 (OP_EQUALVERIFY), stack: []"
+        );
+
+        let script = Script::from_asm("1 1234 OP_ROLL").unwrap();
+        let error = eval_script(
+            &mut Vec::new(),
+            &script,
+            VerifyFlags::default(),
+            &NoSignatures,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot reach stack element 1234 at instruction 2 (OP_ROLL), stack: [01]"
+        );
+    }
+
+    #[test]
+    fn test_eval_script_keeps_stack() {
+        let mut stack = vec![vec![2]];
+        let script = Script::from_asm("OP_DUP 3").unwrap();
+        eval_script(&mut stack, &script, VerifyFlags::default(), &NoSignatures).unwrap();
+
+        assert_eq!(stack, vec![vec![2], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_signature_removed_from_script_code() {
+        let signature = vec![0x30, 0x01, 0x01];
+        let pubkey = vec![0x02; 33];
+        let checker = RecordingChecker(RefCell::new(Vec::new()));
+        let script = Script::new(vec![
+            Instruction::push(signature.clone()),
+            Instruction::Op(OP_DROP),
+            Instruction::Op(OP_CODESEPARATOR),
+            Instruction::push(signature.clone()),
+            Instruction::push(signature),
+            Instruction::push(pubkey.clone()),
+            Instruction::Op(OP_CHECKSIG),
+        ])
+        .unwrap();
+
+        let result = eval_script(&mut Vec::new(), &script, VerifyFlags::default(), &checker);
+
+        assert_eq!(result, Ok(()));
+        let expected = Script::new(vec![
+            Instruction::push(pubkey),
+            Instruction::Op(OP_CHECKSIG),
+        ])
+        .unwrap();
+        assert_eq!(*checker.0.borrow(), vec![expected]);
+    }
+
+    struct NoSignatures;
+
+    impl SignatureChecker for NoSignatures {
+        fn check_signature(&self, _: &[u8], _: &[u8], _: &Script, _: VerifyFlags) -> bool {
+            false
+        }
+    }
+
+    // Accepts every signature, recording the script code it was asked about.
+    struct RecordingChecker(RefCell<Vec<Script>>);
+
+    impl SignatureChecker for RecordingChecker {
+        fn check_signature(
+            &self,
+            _: &[u8],
+            _: &[u8],
+            script_code: &Script,
+            _: VerifyFlags,
+        ) -> bool {
+            self.0.borrow_mut().push(script_code.clone());
+            true
+        }
+    }
+
+    // Spends the output of a transaction paying to `script_pubkey`, as the
+    // Bitcoin Core test does.
+    fn run_script_test(
+        script_sig: &str,
+        script_pubkey: &str,
+        flags: VerifyFlags,
+    ) -> Result<(), InterpreterError> {
+        let script_sig = parse_test_script(script_sig);
+        let script_pubkey = parse_test_script(script_pubkey);
+
+        let credit = Tx::new(
+            1,
+            vec![TxIn::new(
+                OutPoint::new([0; 32], 0xffffffff),
+                vec![OP_0, OP_0],
+                0xffffffff,
+            )],
+            vec![TxOut::new(0, script_pubkey.clone())],
+            0,
+        );
+        let spend = Tx::new(
+            1,
+            vec![TxIn::new(
+                OutPoint::new(credit.txid(), 0),
+                script_sig.clone(),
+                0xffffffff,
+            )],
+            vec![TxOut::new(0, Vec::new())],
+            0,
+        );
+
+        let checker = TransactionSignatureChecker::new(&spend, 0);
+        verify_script(
+            &Script::from_bytes(&script_sig),
+            &Script::from_bytes(&script_pubkey),
+            flags,
+            &checker,
+        )
+    }
+
+    // The script notation of Bitcoin Core's tests: numbers, raw `0x` bytes,
+    // quoted strings to push and opcode names with or without `OP_`.
+    fn parse_test_script(script: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for token in script.split_whitespace() {
+            let digits = token.strip_prefix('-').unwrap_or(token);
+            if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                let value = token.parse().unwrap();
+                bytes.extend(
+                    Script::new(vec![Instruction::push_num(value)])
+                        .unwrap()
+                        .serialize(),
+                );
+            } else if let Some(digits) = token.strip_prefix("0x") {
+                bytes.extend(hex::decode(digits).unwrap());
+            } else if let Some(text) = token.strip_prefix('\'') {
+                let text = text.strip_suffix('\'').unwrap();
+                let push = Instruction::push(text.as_bytes().to_vec());
+                bytes.extend(Script::new(vec![push]).unwrap().serialize());
+            } else {
+                let name = token.strip_prefix("OP_").unwrap_or(token);
+                let opcode = (OP_NOP..=u8::MAX)
+                    .chain([OP_RESERVED])
+                    .find(|&opcode| opcode::name(opcode) == format!("OP_{}", name))
+                    .unwrap_or_else(|| panic!("unknown opcode {}", token));
+                bytes.push(opcode);
+            }
+        }
+        bytes
+    }
+
+    // `None` if any of the flags is not implemented.
+    fn parse_flags(flags: &str) -> Option<VerifyFlags> {
+        let mut parsed = VerifyFlags::default();
+        for flag in flags.split(',').filter(|flag| !flag.is_empty()) {
+            match flag {
+                "NONE" => {}
+                "P2SH" => parsed.p2sh = true,
+                "STRICTENC" => parsed.strict_encoding = true,
+                _ => return None,
+            }
+        }
+        Some(parsed)
+    }
+
+    fn core_name(error: &InterpreterError) -> &'static str {
+        match error {
+            InterpreterError::EvalFalse(_) => "EVAL_FALSE",
+            InterpreterError::OpReturn(_) => "OP_RETURN",
+            InterpreterError::ScriptSize(_) => "SCRIPT_SIZE",
+            InterpreterError::PushSize(_) => "PUSH_SIZE",
+            InterpreterError::OpCount(_) => "OP_COUNT",
+            InterpreterError::StackSize(_) => "STACK_SIZE",
+            InterpreterError::SigCount(_) => "SIG_COUNT",
+            InterpreterError::PubkeyCount(_) => "PUBKEY_COUNT",
+            InterpreterError::Verify(_) => "VERIFY",
+            InterpreterError::EqualVerify(_) => "EQUALVERIFY",
+            InterpreterError::CheckMultisigVerify(_) => "CHECKMULTISIGVERIFY",
+            InterpreterError::CheckSigVerify(_) => "CHECKSIGVERIFY",
+            InterpreterError::NumEqualVerify(_) => "NUMEQUALVERIFY",
+            InterpreterError::BadOpcode(_) => "BAD_OPCODE",
+            InterpreterError::DisabledOpcode(_) => "DISABLED_OPCODE",
+            InterpreterError::InvalidStackOperation(_) => "INVALID_STACK_OPERATION",
+            InterpreterError::InvalidAltstackOperation(_) => "INVALID_ALTSTACK_OPERATION",
+            InterpreterError::UnbalancedConditional(_) => "UNBALANCED_CONDITIONAL",
+            InterpreterError::SigHashType(_) => "SIG_HASHTYPE",
+            InterpreterError::SigDer(_) => "SIG_DER",
+            InterpreterError::PubkeyType(_) => "PUBKEYTYPE",
+            InterpreterError::SigPushOnly(_) => "SIG_PUSHONLY",
+            InterpreterError::NumberOverflow(_) => "UNKNOWN_ERROR",
+        }
+    }
+}
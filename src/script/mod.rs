@@ -0,0 +1,8 @@
+pub mod instruction;
+pub mod interpreter;
+pub mod number;
+pub mod opcode;
+mod program;
+pub mod signature_checker;
+
+pub use program::{Script, ScriptError};
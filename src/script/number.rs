@@ -0,0 +1,75 @@
+/// Encodes `value` the way script arithmetic stores numbers: minimal
+/// little-endian magnitude with the sign in the top bit of the last byte.
+/// Zero is the empty vector.
+pub fn encode_num(value: i64) -> Vec<u8> {
+    let mut magnitude = value.unsigned_abs();
+    let mut bytes = Vec::new();
+    while magnitude > 0 {
+        bytes.push(magnitude as u8);
+        magnitude >>= 8;
+    }
+
+    let sign = if value < 0 { 0x80 } else { 0x00 };
+    match bytes.last_mut() {
+        // The top bit is taken by the magnitude, so the sign needs a byte.
+        Some(last) if *last & 0x80 != 0 => bytes.push(sign),
+        Some(last) => *last |= sign,
+        None => {}
+    }
+    bytes
+}
+
+/// Decodes a number of up to 8 bytes written by `encode_num`, also accepting
+/// non-minimal encodings such as negative zero.
+///
+/// Panics if `bytes` is longer than 8 bytes.
+pub fn decode_num(bytes: &[u8]) -> i64 {
+    assert!(
+        bytes.len() <= 8,
+        "Cannot decode a {} byte number",
+        bytes.len()
+    );
+    let Some((&last, _)) = bytes.split_last() else {
+        return 0;
+    };
+
+    let magnitude = bytes.iter().rev().fold(0u64, |acc, &b| acc << 8 | b as u64)
+        & !(0x80u64 << (8 * (bytes.len() - 1)));
+    if last & 0x80 != 0 {
+        -(magnitude as i64)
+    } else {
+        magnitude as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_num() {
+        let cases: [(i64, &[u8]); 9] = [
+            (0, &[]),
+            (1, &[0x01]),
+            (-1, &[0x81]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x00]),
+            (-128, &[0x80, 0x80]),
+            (255, &[0xff, 0x00]),
+            (256, &[0x00, 0x01]),
+            (-2147483647, &[0xff, 0xff, 0xff, 0xff]),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode_num(value), bytes);
+            assert_eq!(decode_num(bytes), value);
+        }
+    }
+
+    #[test]
+    fn test_decode_non_minimal() {
+        assert_eq!(decode_num(&[0x80]), 0);
+        assert_eq!(decode_num(&[0x00, 0x00]), 0);
+        assert_eq!(decode_num(&[0x01, 0x00, 0x80]), -1);
+    }
+}
@@ -0,0 +1,543 @@
+use std::fmt;
+
+use super::instruction::Instruction;
+use super::number::decode_num;
+use super::opcode::{
+    self, OP_0, OP_16, OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY, OP_HASH160, OP_PUSHDATA1,
+    OP_PUSHDATA2, OP_PUSHDATA4,
+};
+
+/// A script as a sequence of instructions. Push encodings are kept, so a
+/// parsed script serializes back to the same bytes.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Script {
+    instructions: Vec<Instruction>,
+    // The bytes of a last push that runs past the end of the script, kept
+    // by `from_bytes` so the interpreter fails only when it reaches them.
+    truncated_push: Option<Vec<u8>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ScriptError {
+    TruncatedPush(String),
+    InvalidPush(String),
+    InvalidAsm(String),
+}
+
+impl Script {
+    pub fn new(instructions: Vec<Instruction>) -> Result<Script, ScriptError> {
+        for instruction in &instructions {
+            check_instruction(instruction)?;
+        }
+        Ok(Script {
+            instructions,
+            truncated_push: None,
+        })
+    }
+
+    /// `OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG`, paying to the
+    /// hash160 of a SEC public key.
+    pub fn p2pkh(hash: &[u8; 20]) -> Script {
+        Script {
+            instructions: vec![
+                Instruction::Op(OP_DUP),
+                Instruction::Op(OP_HASH160),
+                Instruction::push(hash.to_vec()),
+                Instruction::Op(OP_EQUALVERIFY),
+                Instruction::Op(OP_CHECKSIG),
+            ],
+            truncated_push: None,
+        }
+    }
+
+    /// `OP_0 <hash>`, the version 0 witness program paying to the hash160 of
+    /// a compressed SEC public key.
+    pub fn p2wpkh(hash: &[u8; 20]) -> Script {
+        Script {
+            instructions: vec![
+                Instruction::push(Vec::new()),
+                Instruction::push(hash.to_vec()),
+            ],
+            truncated_push: None,
+        }
+    }
+
+    /// The complete instructions, without a truncated push at the end.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// The bytes of a push at the end of the script that runs past its end,
+    /// from the push opcode on. Only scripts built with `from_bytes` have one.
+    pub fn truncated_push(&self) -> Option<&[u8]> {
+        self.truncated_push.as_deref()
+    }
+
+    /// Whether the script is exactly `OP_HASH160 <20 bytes> OP_EQUAL`, the
+    /// BIP16 pattern that makes the interpreter also run the redeem script.
+    pub fn is_p2sh(&self) -> bool {
+        self.truncated_push.is_none()
+            && matches!(
+                self.instructions.as_slice(),
+                [
+                    Instruction::Op(OP_HASH160),
+                    Instruction::Push { opcode: 0x14, .. },
+                    Instruction::Op(OP_EQUAL),
+                ]
+            )
+    }
+
+    /// Whether the script only pushes data. As in Bitcoin Core, this counts
+    /// `OP_RESERVED` along with `OP_1NEGATE` and `OP_1` to `OP_16`.
+    pub fn is_push_only(&self) -> bool {
+        self.truncated_push.is_none()
+            && self
+                .instructions
+                .iter()
+                .all(|instruction| instruction.opcode() <= OP_16)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Script, ScriptError> {
+        let (instructions, truncated) = parse_instructions(bytes);
+        if let Some((_, e)) = truncated {
+            return Err(e);
+        }
+
+        Ok(Script {
+            instructions,
+            truncated_push: None,
+        })
+    }
+
+    /// Parses a script from a transaction. Unlike `parse`, a push that runs
+    /// past the end of the script is kept as is, since Bitcoin Core only
+    /// fails such a script when it runs into that push.
+    pub fn from_bytes(bytes: &[u8]) -> Script {
+        let (instructions, truncated) = parse_instructions(bytes);
+        Script {
+            instructions,
+            truncated_push: truncated.map(|(start, _)| bytes[start..].to_vec()),
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for instruction in &self.instructions {
+            bytes.push(instruction.opcode());
+            if let Instruction::Push { opcode, data } = instruction {
+                let len = data.len() as u32;
+                match *opcode {
+                    OP_PUSHDATA1 => bytes.push(len as u8),
+                    OP_PUSHDATA2 => bytes.extend((len as u16).to_le_bytes()),
+                    OP_PUSHDATA4 => bytes.extend(len.to_le_bytes()),
+                    _ => {}
+                }
+                bytes.extend(data);
+            }
+        }
+        if let Some(truncated_push) = &self.truncated_push {
+            bytes.extend(truncated_push);
+        }
+        bytes
+    }
+
+    /// The script in Bitcoin Core's ASM form: pushes of up to 4 bytes as
+    /// decimal script numbers, longer ones in hex and other opcodes by name.
+    ///
+    /// The form is lossy. It does not say how pushes were encoded, and a
+    /// non-minimal number or an unknown opcode does not parse back. A
+    /// truncated push is shown as `[error]`.
+    pub fn to_asm(&self) -> String {
+        self.instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Push { data, .. } if data.len() <= 4 => decode_num(data).to_string(),
+                Instruction::Push { data, .. } => hex::encode(data),
+                Instruction::Op(opcode) => opcode::name(*opcode).to_string(),
+            })
+            .chain(
+                self.truncated_push
+                    .as_ref()
+                    .map(|_| String::from("[error]")),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses the output of `to_asm`, pushing numbers and data minimally.
+    ///
+    /// A token of up to 10 decimal digits that fits in 4 bytes is a number,
+    /// as `to_asm` prints it. The same text printed as the hex of a 5 byte
+    /// push therefore does not parse back as that push.
+    pub fn from_asm(asm: &str) -> Result<Script, ScriptError> {
+        let instructions = asm
+            .split_whitespace()
+            .map(|token| {
+                if let Some(value) = parse_asm_number(token) {
+                    return Ok(Instruction::push_num(value));
+                }
+                match opcode::from_name(token) {
+                    Some(OP_0) => return Ok(Instruction::push(Vec::new())),
+                    Some(opcode) if opcode > OP_PUSHDATA4 => return Ok(Instruction::Op(opcode)),
+                    _ => {}
+                }
+                match hex::decode(token) {
+                    Ok(data) => Ok(Instruction::push(data)),
+                    Err(_) => Err(ScriptError::InvalidAsm(format!(
+                        "{} is not a number, opcode or hex data",
+                        token
+                    ))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Script {
+            instructions,
+            truncated_push: None,
+        })
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_asm())
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::TruncatedPush(message)
+            | ScriptError::InvalidPush(message)
+            | ScriptError::InvalidAsm(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+// The instructions up to the end of `bytes` or up to a push that runs past
+// it, along with the offset of that push and why it failed.
+fn parse_instructions(bytes: &[u8]) -> (Vec<Instruction>, Option<(usize, ScriptError)>) {
+    let mut instructions = Vec::new();
+    let mut rest = bytes;
+
+    while let Some((&opcode, tail)) = rest.split_first() {
+        let start = bytes.len() - rest.len();
+        rest = tail;
+        if opcode > OP_PUSHDATA4 {
+            instructions.push(Instruction::Op(opcode));
+            continue;
+        }
+
+        let width = match opcode {
+            OP_PUSHDATA1 => 1,
+            OP_PUSHDATA2 => 2,
+            OP_PUSHDATA4 => 4,
+            _ => 0,
+        };
+        if rest.len() < width {
+            let e = ScriptError::TruncatedPush(format!(
+                "{} needs a {} byte length, {} left",
+                opcode::name(opcode),
+                width,
+                rest.len()
+            ));
+            return (instructions, Some((start, e)));
+        }
+        let len = match width {
+            0 => opcode as usize,
+            _ => rest[..width]
+                .iter()
+                .rev()
+                .fold(0, |acc, &b| acc << 8 | b as usize),
+        };
+        rest = &rest[width..];
+
+        if rest.len() < len {
+            let e = ScriptError::TruncatedPush(format!(
+                "push of {} bytes with {} left",
+                len,
+                rest.len()
+            ));
+            return (instructions, Some((start, e)));
+        }
+        let (data, tail) = rest.split_at(len);
+        instructions.push(Instruction::Push {
+            opcode,
+            data: data.to_vec(),
+        });
+        rest = tail;
+    }
+
+    (instructions, None)
+}
+
+fn check_instruction(instruction: &Instruction) -> Result<(), ScriptError> {
+    let (opcode, len) = match instruction {
+        Instruction::Op(opcode) if *opcode <= OP_PUSHDATA4 => {
+            return Err(ScriptError::InvalidPush(format!(
+                "{:#04x} is a push opcode and needs its data",
+                opcode
+            )))
+        }
+        Instruction::Op(_) => return Ok(()),
+        Instruction::Push { opcode, data } => (*opcode, data.len() as u64),
+    };
+
+    let fits = match opcode {
+        OP_PUSHDATA1 => len <= 0xff,
+        OP_PUSHDATA2 => len <= 0xffff,
+        OP_PUSHDATA4 => len <= 0xffffffff,
+        direct if direct < OP_PUSHDATA1 => len == direct as u64,
+        _ => {
+            return Err(ScriptError::InvalidPush(format!(
+                "{} is not a push opcode",
+                opcode::name(opcode)
+            )))
+        }
+    };
+    if !fits {
+        return Err(ScriptError::InvalidPush(format!(
+            "cannot push {} bytes with opcode {:#04x}",
+            len, opcode
+        )));
+    }
+    Ok(())
+}
+
+// Numbers that `to_asm` prints, the values of pushes of up to 4 bytes.
+fn parse_asm_number(token: &str) -> Option<i64> {
+    let digits = token.strip_prefix('-').unwrap_or(token);
+    if digits.is_empty() || digits.len() > 10 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token
+        .parse::<i64>()
+        .ok()
+        .filter(|value| value.unsigned_abs() <= i32::MAX as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptography::private_key::PrivateKey;
+    use crate::elliptic_curve::scalar::Scalar;
+    use crate::script::opcode::{OP_CHECKLOCKTIMEVERIFY, OP_DROP};
+
+    use super::*;
+
+    // Script sig of the first input of mainnet transaction
+    // 452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03.
+    const SCRIPT_SIG: &str = "483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a";
+
+    #[test]
+    fn test_parse() {
+        let script = Script::parse(&hex::decode(SCRIPT_SIG).unwrap()).unwrap();
+
+        assert_eq!(script.instructions().len(), 2);
+        assert_eq!(script.instructions()[0].opcode(), 0x48);
+        assert_eq!(
+            script.instructions()[1],
+            Instruction::Push {
+                opcode: 0x21,
+                data: hex::decode(
+                    "0349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a"
+                )
+                .unwrap()
+            }
+        );
+        assert_eq!(hex::encode(script.serialize()), SCRIPT_SIG);
+    }
+
+    #[test]
+    fn test_pushdata_round_trip() {
+        let raw = hex::decode(format!(
+            "4c03aabbcc4d0001{}4e01000000ff4c004c0101ac00",
+            "11".repeat(256)
+        ))
+        .unwrap();
+        let script = Script::parse(&raw).unwrap();
+
+        let opcodes: Vec<u8> = script.instructions().iter().map(|i| i.opcode()).collect();
+        assert_eq!(
+            opcodes,
+            [
+                OP_PUSHDATA1,
+                OP_PUSHDATA2,
+                OP_PUSHDATA4,
+                OP_PUSHDATA1,
+                OP_PUSHDATA1,
+                OP_CHECKSIG,
+                OP_0
+            ]
+        );
+        assert_eq!(script.serialize(), raw);
+        assert_eq!(Script::new(script.instructions().to_vec()).unwrap(), script);
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        for raw in [
+            "01",
+            "4bff",
+            "4c",
+            "4c02aa",
+            "4d01",
+            "4d0100",
+            "4e000000",
+            "4e01000000",
+        ] {
+            assert!(
+                matches!(
+                    Script::parse(&hex::decode(raw).unwrap()),
+                    Err(ScriptError::TruncatedPush(_))
+                ),
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_keeps_truncated_push() {
+        let raw = hex::decode("0101514c02aa").unwrap();
+        let script = Script::from_bytes(&raw);
+
+        assert_eq!(
+            script.instructions(),
+            [Instruction::push(vec![0x01]), Instruction::Op(0x51)]
+        );
+        assert_eq!(script.truncated_push(), Some(&raw[3..]));
+        assert_eq!(script.serialize(), raw);
+        assert_eq!(script.to_asm(), "1 1 [error]");
+        assert!(!script.is_push_only());
+
+        let raw = hex::decode(SCRIPT_SIG).unwrap();
+        assert_eq!(Script::from_bytes(&raw), Script::parse(&raw).unwrap());
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_pushes() {
+        let invalid = [
+            Instruction::Push {
+                opcode: 0x02,
+                data: vec![0xaa],
+            },
+            Instruction::Push {
+                opcode: OP_PUSHDATA1,
+                data: vec![0; 256],
+            },
+            Instruction::Push {
+                opcode: OP_DUP,
+                data: Vec::new(),
+            },
+            Instruction::Op(OP_PUSHDATA1),
+            Instruction::Op(0x14),
+        ];
+
+        for instruction in invalid {
+            assert!(matches!(
+                Script::new(vec![instruction]),
+                Err(ScriptError::InvalidPush(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_to_asm() {
+        let script = Script::parse(&hex::decode(SCRIPT_SIG).unwrap()).unwrap();
+        assert_eq!(
+            script.to_asm(),
+            "3045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01 0349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a"
+        );
+
+        let script = Script::parse(&hex::decode("0004ff0000804f51600102b1bb").unwrap()).unwrap();
+        assert_eq!(
+            script.to_asm(),
+            "0 -255 -1 1 16 2 OP_CHECKLOCKTIMEVERIFY OP_UNKNOWN"
+        );
+        assert_eq!(Script::default().to_asm(), "");
+    }
+
+    #[test]
+    fn test_from_asm() {
+        let asm =
+            "OP_DUP OP_HASH160 bc3b654dca7e56b04dca18f2566cdaf02e8d9ada OP_EQUALVERIFY OP_CHECKSIG";
+        let script = Script::from_asm(asm).unwrap();
+        assert_eq!(
+            hex::encode(script.serialize()),
+            "76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac"
+        );
+        assert_eq!(script.to_string(), asm);
+
+        let script = Script::from_asm("1700000000 OP_CHECKLOCKTIMEVERIFY OP_DROP").unwrap();
+        assert_eq!(
+            script.instructions(),
+            [
+                Instruction::push(vec![0x00, 0xf1, 0x53, 0x65]),
+                Instruction::Op(OP_CHECKLOCKTIMEVERIFY),
+                Instruction::Op(OP_DROP)
+            ]
+        );
+        assert_eq!(script.to_asm(), "1700000000 OP_CHECKLOCKTIMEVERIFY OP_DROP");
+
+        let script = Script::from_asm("0 -1 16 OP_TRUE OP_1NEGATE 17").unwrap();
+        assert_eq!(hex::encode(script.serialize()), "004f60514f0111");
+        assert_eq!(script.to_asm(), "0 -1 16 1 -1 17");
+
+        for asm in ["OP_FOO", "abc", "OP_PUSHDATA1", "2147483648a", "+1"] {
+            assert!(
+                matches!(Script::from_asm(asm), Err(ScriptError::InvalidAsm(_))),
+                "{}",
+                asm
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_p2sh() {
+        let p2sh = "a9144733f37cf4db86fbc2efed2500b4f4e49f31202387";
+        assert!(Script::parse(&hex::decode(p2sh).unwrap())
+            .unwrap()
+            .is_p2sh());
+
+        // The same hash pushed with OP_PUSHDATA1.
+        let pushdata = "a94c144733f37cf4db86fbc2efed2500b4f4e49f31202387";
+        assert!(!Script::parse(&hex::decode(pushdata).unwrap())
+            .unwrap()
+            .is_p2sh());
+        assert!(!Script::p2pkh(&[0; 20]).is_p2sh());
+    }
+
+    #[test]
+    fn test_is_push_only() {
+        assert!(Script::from_asm("0 -1 16 abcdef 1234567890")
+            .unwrap()
+            .is_push_only());
+        assert!(Script::parse(&[0x50]).unwrap().is_push_only());
+        assert!(Script::default().is_push_only());
+        assert!(!Script::from_asm("1 OP_NOP").unwrap().is_push_only());
+    }
+
+    #[test]
+    fn test_locking_scripts() {
+        let point = PrivateKey::new(Scalar::from(5002u32))
+            .unwrap()
+            .point()
+            .clone();
+
+        let p2pkh = Script::p2pkh(&point.hash160(false));
+        assert_eq!(
+            hex::encode(p2pkh.serialize()),
+            format!("76a914{}88ac", hex::encode(point.hash160(false)))
+        );
+        assert_eq!(Script::parse(&p2pkh.serialize()).unwrap(), p2pkh);
+
+        let p2wpkh = Script::p2wpkh(&point.hash160(true));
+        assert_eq!(
+            hex::encode(p2wpkh.serialize()),
+            format!("0014{}", hex::encode(point.hash160(true)))
+        );
+        assert_eq!(Script::parse(&p2wpkh.serialize()).unwrap(), p2wpkh);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cryptography::signature::Signature;
+use crate::elliptic_curve::point::Point;
+use crate::integer::{BigInteger, Integer};
+use crate::transaction::tx::Tx;
+
+use super::interpreter::VerifyFlags;
+use super::program::Script;
+
+/// Decides whether a signature from the stack, its hash type byte included,
+/// signs for `pubkey`. The interpreter has already checked the encodings its
+/// flags require, and passes them on so the signature can be parsed the same
+/// way.
+pub trait SignatureChecker {
+    fn check_signature(
+        &self,
+        signature: &[u8],
+        pubkey: &[u8],
+        script_code: &Script,
+        flags: VerifyFlags,
+    ) -> bool;
+}
+
+/// Checks signatures against the pre-segwit signature hash of one input of a
+/// transaction.
+pub struct TransactionSignatureChecker<'a> {
+    tx: &'a Tx,
+    input_index: usize,
+}
+
+impl<'a> TransactionSignatureChecker<'a> {
+    pub fn new(tx: &'a Tx, input_index: usize) -> TransactionSignatureChecker<'a> {
+        TransactionSignatureChecker { tx, input_index }
+    }
+}
+
+impl SignatureChecker for TransactionSignatureChecker<'_> {
+    fn check_signature(
+        &self,
+        signature: &[u8],
+        pubkey: &[u8],
+        script_code: &Script,
+        flags: VerifyFlags,
+    ) -> bool {
+        let Some((&hash_type, der)) = signature.split_last() else {
+            return false;
+        };
+        // Signatures from before BIP66 only have to be DER-like.
+        let sig = if flags.strict_encoding {
+            Signature::parse_der(der)
+        } else {
+            Signature::parse_der_lax(der)
+        };
+        let (Ok(sig), Ok(point)) = (sig, Point::parse_sec(pubkey)) else {
+            return false;
+        };
+
+        let z = self
+            .tx
+            .sig_hash(self.input_index, script_code, hash_type as u32);
+        point.verify(&Integer::from_be_bytes(&z), &sig)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod outpoint;
+pub mod tx;
+pub mod tx_in;
+pub mod tx_out;
@@ -0,0 +1,43 @@
+use std::io::{self, Read, Write};
+
+use crate::encoding::fixed_width::{read_array, read_u32_le, write_u32_le};
+
+use super::tx::TransactionError;
+
+/// A reference to an output of a previous transaction.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OutPoint {
+    txid: [u8; 32],
+    index: u32,
+}
+
+impl OutPoint {
+    /// `txid` is in the usual display order, the reverse of how it is
+    /// serialized.
+    pub fn new(txid: [u8; 32], index: u32) -> OutPoint {
+        OutPoint { txid, index }
+    }
+
+    pub fn txid(&self) -> &[u8; 32] {
+        &self.txid
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<OutPoint, TransactionError> {
+        let mut txid: [u8; 32] = read_array(reader)?;
+        txid.reverse();
+        let index = read_u32_le(reader)?;
+
+        Ok(OutPoint { txid, index })
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut txid = self.txid;
+        txid.reverse();
+        writer.write_all(&txid)?;
+        write_u32_le(writer, self.index)
+    }
+}
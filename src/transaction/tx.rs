@@ -0,0 +1,558 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use crate::cryptography::hashing::hash256;
+use crate::encoding::fixed_width::{read_array, read_u32_le, write_u32_le};
+use crate::encoding::varint::{read_varint, write_varint, VarintError};
+use crate::script::{instruction::Instruction, opcode::OP_CODESEPARATOR, Script};
+
+use super::tx_in::TxIn;
+use super::tx_out::TxOut;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Tx {
+    version: u32,
+    inputs: Vec<TxIn>,
+    outputs: Vec<TxOut>,
+    locktime: u32,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TransactionError {
+    ReadError(String),
+    NonCanonicalVarint(String),
+    UnknownFlag(String),
+    SuperfluousWitness(String),
+}
+
+// Segwit serializations put a zero marker, which a legacy transaction cannot
+// have as its input count, and a flag byte after the version.
+const SEGWIT_MARKER: u8 = 0x00;
+const SEGWIT_FLAG: u8 = 0x01;
+
+// Base bytes count four times towards the weight, witness bytes once.
+const WITNESS_SCALE_FACTOR: usize = 4;
+
+pub const SIGHASH_ALL: u32 = 0x01;
+pub const SIGHASH_NONE: u32 = 0x02;
+pub const SIGHASH_SINGLE: u32 = 0x03;
+pub const SIGHASH_ANYONECANPAY: u32 = 0x80;
+
+impl Tx {
+    pub fn new(version: u32, inputs: Vec<TxIn>, outputs: Vec<TxOut>, locktime: u32) -> Tx {
+        Tx {
+            version,
+            inputs,
+            outputs,
+            locktime,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn inputs(&self) -> &[TxIn] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[TxOut] {
+        &self.outputs
+    }
+
+    pub fn locktime(&self) -> u32 {
+        self.locktime
+    }
+
+    /// Whether any input carries witness data, which makes the transaction
+    /// serialize in the BIP144 format.
+    pub fn is_segwit(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness().is_empty())
+    }
+
+    /// The hash256 of the transaction serialized without witness data,
+    /// reversed into the byte order block explorers display.
+    pub fn txid(&self) -> [u8; 32] {
+        let mut txid = hash256(&self.serialize_legacy());
+        txid.reverse();
+        txid
+    }
+
+    /// Like `txid` but over the serialization including witness data. Equal
+    /// to the txid for transactions without witnesses.
+    pub fn wtxid(&self) -> [u8; 32] {
+        let mut wtxid = hash256(&self.serialize());
+        wtxid.reverse();
+        wtxid
+    }
+
+    /// The BIP141 weight: three times the size without witness data plus the
+    /// full size.
+    pub fn weight(&self) -> usize {
+        self.serialize_legacy().len() * (WITNESS_SCALE_FACTOR - 1) + self.serialize().len()
+    }
+
+    /// The weight divided by four, rounded up.
+    pub fn vsize(&self) -> usize {
+        self.weight().div_ceil(WITNESS_SCALE_FACTOR)
+    }
+
+    /// The pre-segwit signature hash of input `input_index` spending an
+    /// output locked by `script_code`, which is what `OP_CHECKSIG` verifies
+    /// signatures against. `hash_type` selects the inputs and outputs that
+    /// are committed to and is appended to the serialization.
+    ///
+    /// Like Bitcoin Core, returns the number 1 as a little-endian hash for an
+    /// input index out of range, or for `SIGHASH_SINGLE` without a matching
+    /// output.
+    pub fn sig_hash(&self, input_index: usize, script_code: &Script, hash_type: u32) -> [u8; 32] {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let base_type = hash_type & 0x1f;
+        if input_index >= self.inputs.len()
+            || (base_type == SIGHASH_SINGLE && input_index >= self.outputs.len())
+        {
+            return one;
+        }
+
+        let script_code = Script::new(
+            script_code
+                .instructions()
+                .iter()
+                .filter(|instruction| **instruction != Instruction::Op(OP_CODESEPARATOR))
+                .cloned()
+                .collect(),
+        )
+        .expect("instructions of a valid script")
+        .serialize();
+        let anyone_can_pay = hash_type & SIGHASH_ANYONECANPAY != 0;
+
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| !anyone_can_pay || i == input_index)
+            .map(|(i, input)| {
+                if i == input_index {
+                    return TxIn::new(
+                        input.previous_output().clone(),
+                        script_code.clone(),
+                        input.sequence(),
+                    );
+                }
+                // Only the signed input's sequence is committed to when the
+                // other inputs may be updated.
+                let sequence = match base_type {
+                    SIGHASH_NONE | SIGHASH_SINGLE => 0,
+                    _ => input.sequence(),
+                };
+                TxIn::new(input.previous_output().clone(), Vec::new(), sequence)
+            })
+            .collect();
+        let outputs = match base_type {
+            SIGHASH_NONE => Vec::new(),
+            // Outputs before the signed one are blanked to an amount of -1
+            // and an empty script.
+            SIGHASH_SINGLE => {
+                let mut outputs = vec![TxOut::new(u64::MAX, Vec::new()); input_index];
+                outputs.push(self.outputs[input_index].clone());
+                outputs
+            }
+            _ => self.outputs.clone(),
+        };
+
+        let mut preimage = Tx::new(self.version, inputs, outputs, self.locktime).serialize();
+        preimage.extend(hash_type.to_le_bytes());
+        hash256(&preimage)
+    }
+
+    /// Parses a transaction in the consensus format: a 4 byte version, the
+    /// varint-prefixed inputs and outputs and a 4 byte locktime, with all
+    /// integers little-endian. The BIP144 format, with a marker and flag after
+    /// the version and the witness stacks before the locktime, is detected
+    /// from the marker.
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Tx, TransactionError> {
+        let version = read_u32_le(reader)?;
+
+        let mut input_count = read_varint(reader)?;
+        let segwit = input_count == SEGWIT_MARKER as u64;
+        if segwit {
+            let [flag] = read_array(reader)?;
+            if flag != SEGWIT_FLAG {
+                return Err(TransactionError::UnknownFlag(format!(
+                    "unknown segwit flag {:#04x}",
+                    flag
+                )));
+            }
+            input_count = read_varint(reader)?;
+        }
+
+        let mut inputs: Vec<TxIn> = (0..input_count)
+            .map(|_| TxIn::parse(reader))
+            .collect::<Result<_, _>>()?;
+        let outputs = (0..read_varint(reader)?)
+            .map(|_| TxOut::parse(reader))
+            .collect::<Result<_, _>>()?;
+
+        if segwit {
+            for input in &mut inputs {
+                input.parse_witness(reader)?;
+            }
+            // The legacy format would have served, so the encoding would not
+            // round trip.
+            if inputs.iter().all(|input| input.witness().is_empty()) {
+                return Err(TransactionError::SuperfluousWitness(String::from(
+                    "segwit serialization without witness data",
+                )));
+            }
+        }
+        let locktime = read_u32_le(reader)?;
+
+        Ok(Tx {
+            version,
+            inputs,
+            outputs,
+            locktime,
+        })
+    }
+
+    /// Writes the BIP144 serialization if any input has witness data and the
+    /// legacy one otherwise.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_with_witness(writer, self.is_segwit())
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.write(&mut buffer)
+            .expect("writing to a Vec cannot fail");
+        buffer
+    }
+
+    /// The serialization without witness data, from which the txid is
+    /// computed.
+    pub fn serialize_legacy(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.write_with_witness(&mut buffer, false)
+            .expect("writing to a Vec cannot fail");
+        buffer
+    }
+
+    fn write_with_witness<W: Write>(&self, writer: &mut W, witness: bool) -> io::Result<()> {
+        write_u32_le(writer, self.version)?;
+        if witness {
+            writer.write_all(&[SEGWIT_MARKER, SEGWIT_FLAG])?;
+        }
+        write_varint(writer, self.inputs.len() as u64)?;
+        for input in &self.inputs {
+            input.write(writer)?;
+        }
+        write_varint(writer, self.outputs.len() as u64)?;
+        for output in &self.outputs {
+            output.write(writer)?;
+        }
+        if witness {
+            for input in &self.inputs {
+                input.write_witness(writer)?;
+            }
+        }
+        write_u32_le(writer, self.locktime)
+    }
+}
+
+impl From<io::Error> for TransactionError {
+    fn from(error: io::Error) -> TransactionError {
+        TransactionError::ReadError(format!("Cannot read transaction: {}", error))
+    }
+}
+
+impl From<VarintError> for TransactionError {
+    fn from(error: VarintError) -> TransactionError {
+        match error {
+            VarintError::ReadError(message) => TransactionError::ReadError(message),
+            VarintError::NonCanonical(message) => TransactionError::NonCanonicalVarint(message),
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionError::ReadError(message)
+            | TransactionError::NonCanonicalVarint(message)
+            | TransactionError::UnknownFlag(message)
+            | TransactionError::SuperfluousWitness(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::transaction::outpoint::OutPoint;
+
+    use super::*;
+
+    // Mainnet transaction 452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03.
+    const TX: &str = "0100000001813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1000000006b483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278afeffffff02a135ef01000000001976a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac99c39800000000001976a9141c4bc762dd5423e332166702cb75f40df79fea1288ac19430600";
+
+    #[test]
+    fn test_parse() {
+        let tx = Tx::parse(&mut hex::decode(TX).unwrap().as_slice()).unwrap();
+
+        assert_eq!(tx.version(), 1);
+        assert_eq!(tx.locktime(), 410393);
+
+        assert_eq!(tx.inputs().len(), 1);
+        let input = &tx.inputs()[0];
+        assert_eq!(
+            hex::encode(input.previous_output().txid()),
+            "d1c789a9c60383bf715f3f6ad9d14b91fe55f3deb369fe5d9280cb1a01793f81"
+        );
+        assert_eq!(input.previous_output().index(), 0);
+        assert_eq!(
+            hex::encode(input.script_sig()),
+            "483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a"
+        );
+        assert_eq!(input.sequence(), 0xfffffffe);
+
+        assert_eq!(tx.outputs().len(), 2);
+        assert_eq!(tx.outputs()[0].amount(), 32454049);
+        assert_eq!(
+            hex::encode(tx.outputs()[0].script_pubkey()),
+            "76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac"
+        );
+        assert_eq!(tx.outputs()[1].amount(), 10011545);
+        assert_eq!(
+            hex::encode(tx.outputs()[1].script_pubkey()),
+            "76a9141c4bc762dd5423e332166702cb75f40df79fea1288ac"
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let tx = Tx::parse(&mut hex::decode(TX).unwrap().as_slice()).unwrap();
+        assert_eq!(hex::encode(tx.serialize()), TX);
+
+        let built = Tx::new(
+            tx.version(),
+            vec![TxIn::new(
+                OutPoint::new(
+                    *tx.inputs()[0].previous_output().txid(),
+                    tx.inputs()[0].previous_output().index(),
+                ),
+                tx.inputs()[0].script_sig().to_vec(),
+                0xfffffffe,
+            )],
+            tx.outputs().to_vec(),
+            410393,
+        );
+        assert_eq!(built, tx);
+    }
+
+    #[test]
+    fn test_txid() {
+        let tx = Tx::parse(&mut hex::decode(TX).unwrap().as_slice()).unwrap();
+
+        assert_eq!(
+            hex::encode(tx.txid()),
+            "452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03"
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        let bytes = hex::decode(TX).unwrap();
+
+        for len in [0, 3, 5, 40, 100, bytes.len() - 1] {
+            assert!(matches!(
+                Tx::parse(&mut &bytes[..len]),
+                Err(TransactionError::ReadError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_non_canonical_input_count() {
+        let mut bytes = hex::decode(TX).unwrap();
+        bytes.splice(4..5, [0xfd, 0x01, 0x00]);
+
+        assert!(matches!(
+            Tx::parse(&mut bytes.as_slice()),
+            Err(TransactionError::NonCanonicalVarint(_))
+        ));
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let fixtures: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/transactions.json"
+        )))
+        .unwrap();
+
+        for case in fixtures["transactions"].as_array().unwrap() {
+            let comment = case["comment"].as_str().unwrap();
+            let raw = case["hex"].as_str().unwrap();
+            let tx = Tx::parse(&mut hex::decode(raw).unwrap().as_slice()).unwrap();
+
+            assert_eq!(hex::encode(tx.serialize()), raw, "{}", comment);
+            assert_eq!(hex::encode(tx.txid()), case["txid"], "{}", comment);
+            assert_eq!(hex::encode(tx.wtxid()), case["wtxid"], "{}", comment);
+            assert_eq!(tx.serialize().len() as u64, case["size"], "{}", comment);
+            assert_eq!(tx.weight() as u64, case["weight"], "{}", comment);
+            assert_eq!(tx.vsize() as u64, case["vsize"], "{}", comment);
+            assert_eq!(tx.is_segwit(), case["txid"] != case["wtxid"], "{}", comment);
+
+            let legacy = Tx::parse(&mut tx.serialize_legacy().as_slice()).unwrap();
+            assert!(!legacy.is_segwit());
+            assert_eq!(legacy.txid(), tx.txid(), "{}", comment);
+        }
+    }
+
+    #[test]
+    fn test_parse_witness() {
+        // BIP143 native P2WPKH example: a P2PK input and a P2WPKH input.
+        let raw = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+        let tx = Tx::parse(&mut hex::decode(raw).unwrap().as_slice()).unwrap();
+
+        assert!(tx.inputs()[0].witness().is_empty());
+        assert!(tx.inputs()[1].script_sig().is_empty());
+        assert_eq!(tx.inputs()[1].witness().len(), 2);
+        assert_eq!(
+            hex::encode(&tx.inputs()[1].witness()[1]),
+            "025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee6357"
+        );
+        assert_eq!(tx.locktime(), 17);
+
+        let rebuilt = Tx::new(
+            tx.version(),
+            vec![
+                tx.inputs()[0].clone(),
+                TxIn::with_witness(
+                    tx.inputs()[1].previous_output().clone(),
+                    Vec::new(),
+                    tx.inputs()[1].sequence(),
+                    tx.inputs()[1].witness().to_vec(),
+                ),
+            ],
+            tx.outputs().to_vec(),
+            tx.locktime(),
+        );
+        assert_eq!(hex::encode(rebuilt.serialize()), raw);
+    }
+
+    #[test]
+    fn test_parse_invalid_segwit() {
+        let tx = Tx::parse(&mut hex::decode(TX).unwrap().as_slice()).unwrap();
+        let legacy = tx.serialize();
+
+        let mut unknown_flag = legacy.clone();
+        unknown_flag.splice(4..4, [0x00, 0x02]);
+        assert!(matches!(
+            Tx::parse(&mut unknown_flag.as_slice()),
+            Err(TransactionError::UnknownFlag(_))
+        ));
+
+        // Marker, flag and an empty witness stack for the only input.
+        let mut superfluous = legacy.clone();
+        superfluous.splice(legacy.len() - 4..legacy.len() - 4, [0x00]);
+        superfluous.splice(4..4, [0x00, 0x01]);
+        assert!(matches!(
+            Tx::parse(&mut superfluous.as_slice()),
+            Err(TransactionError::SuperfluousWitness(_))
+        ));
+    }
+
+    #[test]
+    fn test_sig_hash() {
+        let tx = Tx::parse(&mut hex::decode(TX).unwrap().as_slice()).unwrap();
+        let script_pubkey = Script::parse(
+            &hex::decode("76a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(tx.sig_hash(0, &script_pubkey, SIGHASH_ALL)),
+            "27e0c5994dec7824e56dec6b2fcb342eb7cdb0d0957c2fce9882f715e85d81a6"
+        );
+
+        let mut instructions = script_pubkey.instructions().to_vec();
+        instructions.insert(2, Instruction::Op(OP_CODESEPARATOR));
+        instructions.push(Instruction::Op(OP_CODESEPARATOR));
+        let with_separators = Script::new(instructions).unwrap();
+        assert_eq!(
+            tx.sig_hash(0, &with_separators, SIGHASH_ALL),
+            tx.sig_hash(0, &script_pubkey, SIGHASH_ALL)
+        );
+    }
+
+    #[test]
+    fn test_sig_hash_types() {
+        // BIP143 native P2WPKH example, hashed the pre-segwit way. The
+        // expected hashes come from a separate implementation.
+        let raw = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+        let tx = Tx::parse(&mut hex::decode(raw).unwrap().as_slice()).unwrap();
+        let script_code = Script::parse(
+            &hex::decode("76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac").unwrap(),
+        )
+        .unwrap();
+
+        let cases = [
+            (
+                0,
+                SIGHASH_ALL,
+                "26db79e8ac0d23b75d43a013790590c6447dcfbc7eb1a0382f166b181e813960",
+            ),
+            (
+                1,
+                SIGHASH_NONE,
+                "68fec891e0f198db75e7d7e985f9293f2855fd33617864341717ec877b4a149e",
+            ),
+            (
+                1,
+                SIGHASH_SINGLE,
+                "6840d5b4d994feec330dab0912d483672ef70c3aa4852b094ced743fd45947cc",
+            ),
+            (
+                0,
+                SIGHASH_ALL | SIGHASH_ANYONECANPAY,
+                "2e8b5e8400b1ab258d69672099d0bbd9a7475f4599109ab9c5e031aa82830556",
+            ),
+            (
+                1,
+                SIGHASH_SINGLE | SIGHASH_ANYONECANPAY,
+                "f339471a3274f22e70f1d4a4eafc188e864c654820a0cfdc1adad156a6f561b7",
+            ),
+            (
+                2,
+                SIGHASH_ALL,
+                "0100000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+        for (input_index, hash_type, expected) in cases {
+            assert_eq!(
+                hex::encode(tx.sig_hash(input_index, &script_code, hash_type)),
+                expected,
+                "{} {}",
+                input_index,
+                hash_type
+            );
+        }
+
+        // SIGHASH_SINGLE without a matching output.
+        let one_output = Tx::new(
+            tx.version(),
+            tx.inputs().to_vec(),
+            tx.outputs()[..1].to_vec(),
+            tx.locktime(),
+        );
+        assert_eq!(
+            hex::encode(one_output.sig_hash(1, &script_code, SIGHASH_SINGLE)),
+            "0100000000000000000000000000000000000000000000000000000000000000"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{self, Read, Write};
+
+use crate::encoding::fixed_width::{read_u32_le, write_u32_le};
+use crate::encoding::varint::{read_var_bytes, read_varint, write_var_bytes, write_varint};
+
+use super::outpoint::OutPoint;
+use super::tx::TransactionError;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxIn {
+    previous_output: OutPoint,
+    script_sig: Vec<u8>,
+    sequence: u32,
+    witness: Vec<Vec<u8>>,
+}
+
+impl TxIn {
+    pub fn new(previous_output: OutPoint, script_sig: Vec<u8>, sequence: u32) -> TxIn {
+        TxIn {
+            previous_output,
+            script_sig,
+            sequence,
+            witness: Vec::new(),
+        }
+    }
+
+    /// An input spending a segwit output, whose unlocking data goes in
+    /// `witness` rather than the script sig.
+    pub fn with_witness(
+        previous_output: OutPoint,
+        script_sig: Vec<u8>,
+        sequence: u32,
+        witness: Vec<Vec<u8>>,
+    ) -> TxIn {
+        TxIn {
+            previous_output,
+            script_sig,
+            sequence,
+            witness,
+        }
+    }
+
+    pub fn previous_output(&self) -> &OutPoint {
+        &self.previous_output
+    }
+
+    pub fn script_sig(&self) -> &[u8] {
+        &self.script_sig
+    }
+
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    pub fn witness(&self) -> &[Vec<u8>] {
+        &self.witness
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<TxIn, TransactionError> {
+        let previous_output = OutPoint::parse(reader)?;
+        let script_sig = read_var_bytes(reader)?;
+        let sequence = read_u32_le(reader)?;
+
+        Ok(TxIn {
+            previous_output,
+            script_sig,
+            sequence,
+            witness: Vec::new(),
+        })
+    }
+
+    /// Reads the witness stack, which segwit transactions serialize after all
+    /// the outputs.
+    pub(crate) fn parse_witness<R: Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<(), TransactionError> {
+        self.witness = (0..read_varint(reader)?)
+            .map(|_| read_var_bytes(reader))
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.previous_output.write(writer)?;
+        write_var_bytes(writer, &self.script_sig)?;
+        write_u32_le(writer, self.sequence)
+    }
+
+    pub(crate) fn write_witness<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_varint(writer, self.witness.len() as u64)?;
+        for item in &self.witness {
+            write_var_bytes(writer, item)?;
+        }
+        Ok(())
+    }
+}
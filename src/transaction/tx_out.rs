@@ -0,0 +1,45 @@
+use std::io::{self, Read, Write};
+
+use crate::encoding::fixed_width::{read_u64_le, write_u64_le};
+use crate::encoding::varint::{read_var_bytes, write_var_bytes};
+
+use super::tx::TransactionError;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxOut {
+    amount: u64,
+    script_pubkey: Vec<u8>,
+}
+
+impl TxOut {
+    /// `amount` is in satoshis.
+    pub fn new(amount: u64, script_pubkey: Vec<u8>) -> TxOut {
+        TxOut {
+            amount,
+            script_pubkey,
+        }
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn script_pubkey(&self) -> &[u8] {
+        &self.script_pubkey
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<TxOut, TransactionError> {
+        let amount = read_u64_le(reader)?;
+        let script_pubkey = read_var_bytes(reader)?;
+
+        Ok(TxOut {
+            amount,
+            script_pubkey,
+        })
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_u64_le(writer, self.amount)?;
+        write_var_bytes(writer, &self.script_pubkey)
+    }
+}